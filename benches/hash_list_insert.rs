@@ -6,7 +6,7 @@ type Gap = HyperLogLog<
     Precision14,
     Bits5,
     <Precision14 as PackedRegister<Bits5>>::Array,
-    twox_hash::XxHash64,
+    core::hash::BuildHasherDefault<twox_hash::XxHash64>,
 >;
 
 fn bench_hash_list_insert(c: &mut Criterion) {
@@ -26,6 +26,35 @@ fn bench_hash_list_insert(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_hash_list_insert);
+fn bench_hash_list_insert_many(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_list_insert_many");
+    let random_values: Vec<u64> = iter_random_values::<u64>(12_000, None, None).collect();
+
+    group.bench_function("repeated_insert", |b| {
+        b.iter(|| {
+            let mut switch: Gap = Gap::default();
+            for random_value in &random_values {
+                switch.insert(black_box(random_value));
+            }
+            switch
+        });
+    });
+
+    group.bench_function("insert_many", |b| {
+        b.iter(|| {
+            let mut switch: Gap = Gap::default();
+            switch.insert_many(black_box(&random_values));
+            switch
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_hash_list_insert,
+    bench_hash_list_insert_many
+);
 
 criterion_main!(benches);
@@ -1,4 +1,5 @@
 //! Bench to compare and optimize time performance of inserting a prefix-free encoded list of hashes.
+use core::hash::BuildHasherDefault;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use hyperloglog_rs::prelude::*;
 use twox_hash::XxHash64;
@@ -7,24 +8,23 @@ type HLLW = HyperLogLog<
     Precision9,
     Bits6,
     <Precision9 as PackedRegister<Bits6>>::Array,
-    wyhash::WyHash,
+    BuildHasherDefault<wyhash::WyHash>,
 >;
 
 type HLLX = HyperLogLog<
     Precision9,
     Bits6,
     <Precision9 as PackedRegister<Bits6>>::Array,
-    XxHash64
+    BuildHasherDefault<XxHash64>,
 >;
 
 type HLLA = HyperLogLog<
     Precision9,
     Bits6,
     <Precision9 as PackedRegister<Bits6>>::Array,
-    ahash::AHasher,
+    BuildHasherDefault<ahash::AHasher>,
 >;
 
-
 fn bench_hyperloglog_insert(c: &mut Criterion) {
     let mut group = c.benchmark_group("hyperloglog_insert");
 
@@ -342,13 +342,13 @@ pub fn test_estimator(_attr: TokenStream, item: TokenStream) -> TokenStream {
                         #[test]
                         #(#feature_constraints)*
                         fn #array_test_fn_name() {
-                            #fn_name::<#precision, #bit, <#precision as PackedRegister<#bit>>::Array, #hasher>();
+                            #fn_name::<#precision, #bit, <#precision as PackedRegister<#bit>>::Array, core::hash::BuildHasherDefault<#hasher>>();
                         }
                         #[test]
                         #[cfg(feature = "alloc")]
                         #(#feature_constraints)*
                         fn #vec_test_fn_name() {
-                            #fn_name::<#precision, #bit, <#precision as PackedRegister<#bit>>::Vec, #hasher>();
+                            #fn_name::<#precision, #bit, <#precision as PackedRegister<#bit>>::Vec, core::hash::BuildHasherDefault<#hasher>>();
                         }
                     }
             })
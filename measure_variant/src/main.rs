@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use statistical_comparisons::prelude::{
     AlecHLL, CloudFlareHLL, HyperTwoBits, RustHLL, SimpleHLL, SourMash, TabacHLL, TabacHLLPlusPlus,
 };
+use std::hash::BuildHasherDefault;
 use test_utils::prelude::{cardinality_samples, compare_features, ExtendedCardinalitySample, Set};
 use wyhash::WyHash;
 
@@ -90,7 +91,12 @@ fn measure<S: Set + Default + MemSize>(reference: Option<&Measurement>) -> Measu
 /// Main function to compare the progress of two variants of HLL.
 fn main() {
     let reference = measure::<
-        HyperLogLog<Precision9, Bits6, <Precision9 as PackedRegister<Bits6>>::Vec, WyHash>,
+        HyperLogLog<
+            Precision9,
+            Bits6,
+            <Precision9 as PackedRegister<Bits6>>::Vec,
+            BuildHasherDefault<WyHash>,
+        >,
     >(None);
     measure::<CloudFlareHLL<{ Precision9::EXPONENT as usize }, 6, WyHash>>(Some(&reference));
     measure::<SimpleHLL<WyHash, { Precision9::EXPONENT as usize }>>(Some(&reference));
@@ -24,6 +24,7 @@ use serde::Serialize;
 use serde::{Deserialize, Serializer};
 use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
 use std::{u64, u8};
 use test_utils::prelude::{append_csv, read_report, write_report};
 
@@ -126,7 +127,8 @@ struct GapReport {
     extra_hashes: u64,
 }
 
-type H<P, B> = HyperLogLog<P, B, <P as PackedRegister<B>>::Array, twox_hash::XxHash>;
+type H<P, B> =
+    HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<twox_hash::XxHash>>;
 
 /// Measures the gap between subsequent hashes in the Listhash variant of HyperLogLog.
 fn optimal_gap_codes<P: Precision, B: Bits>(multiprogress: &MultiProgress)
@@ -160,6 +162,9 @@ where
     let number_of_bits =
         ((1usize << P::EXPONENT) * usize::from(B::NUMBER_OF_BITS)).div_ceil(64) * 64;
 
+    // The counter is only employed to hash the values.
+    let counter = H::<P, B>::default();
+
     let gaps: HashMap<u8, CodesStats> = ParallelIterator::reduce(
         (0..iterations as u64)
             .into_par_iter()
@@ -182,7 +187,7 @@ where
 
                 for value in iter_random_values::<u64>(1_000_000, None, Some(random_state)) {
                     let (index, register, original_hash) =
-                        H::<P, B>::index_and_register_and_hash(&value);
+                        counter.index_and_register_and_hash(&value);
                     let encoded_hash =
                         GapHash::<P, B>::encode(index, register, original_hash, hash_bits);
 
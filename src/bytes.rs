@@ -0,0 +1,741 @@
+//! Compact and versioned binary serialization of the counters.
+//!
+//! The serialized counter is composed of a fixed-size header followed by a payload:
+//!
+//! ```text
+//! | magic (4) | version (1) | precision (1) | bits (1) | mode (1) | hasher (8) | payload length (4) | checksum (4) | payload |
+//! ```
+//!
+//! All integers are stored in little-endian order. The hasher identifier is the hash of
+//! a fixed probe computed with the builder of the counter, so that counters built with
+//! different hashers or seeds are rejected when loaded. The checksum is the CRC-32 of
+//! the payload: as it only detects accidental corruption, the payload is also validated
+//! structurally when loaded, so that no payload may result in a counter that panics.
+//!
+//! In registers mode, the payload contains the registers, packed one after the other using
+//! exactly `B::NUMBER_OF_BITS` bits each, starting from the most significant bit of the first
+//! byte. The harmonic sum is not stored, as it is recomputed from the registers when loaded. In hash list mode, the payload contains the hash
+//! list metadata, followed by the bytes of the hash list up to the writer tell and, when
+//! present, by the rank index. Both the registers and the hash list are stored in their
+//! canonical layout, so that counters serialized on an architecture can be loaded and merged
+//! on architectures with a different endianness.
+use crate::composite_hash::GapHash;
+use crate::hash_list::{MAXIMAL_DUPLICATES, MAXIMAL_NUMBER_OF_HASHES};
+use crate::hyperloglog::maximal_register_value;
+use crate::prelude::*;
+
+/// The version of the binary serialization format.
+pub const SERIALIZATION_FORMAT_VERSION: u8 = 1;
+
+/// The number of bytes of the header of the binary serialization format.
+pub const SERIALIZATION_HEADER_SIZE: usize = 24;
+
+/// The magic number at the beginning of all serialized counters.
+const MAGIC: [u8; 4] = *b"HLLR";
+
+/// The mode identifier of counters serialized in registers mode.
+const REGISTERS_MODE: u8 = 0;
+
+/// The mode identifier of counters serialized in hash list mode.
+const HASH_LIST_MODE: u8 = 1;
+
+/// The number of bytes of the hash list metadata at the beginning of the payload.
+const HASH_LIST_METADATA_SIZE: usize = 17;
+
+#[inline]
+/// Returns the CRC-32 (IEEE 802.3) checksum of the provided bytes.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & 0_u32.wrapping_sub(crc & 1));
+        }
+    }
+    !crc
+}
+
+#[inline]
+/// Reads a little-endian u32 from the provided bytes at the provided offset.
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline]
+/// Reads a little-endian u64 from the provided bytes at the provided offset.
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B, R, H> {
+    #[inline]
+    /// Returns the number of bytes of the registers packed without padding.
+    fn packed_registers_size() -> usize {
+        ((1_usize << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE).div_ceil(8)
+    }
+
+    #[inline]
+    /// Returns the number of bytes of the rank index stored at the end of the hash list.
+    fn rank_index_size(storage_size: usize, hash_bits: u8) -> usize {
+        if storage_size > GapHash::<P, B>::maximal_number_of_bytes() / 2 {
+            (GapHash::<P, B>::rank_index_padded_size(hash_bits) / 8) as usize
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    /// Returns the number of bytes of the payload of the serialized counter.
+    fn payload_size(&self) -> usize {
+        if self.is_hash_list() {
+            let storage_size = <R as AsRef<[u8]>>::as_ref(&self.registers).len();
+            HASH_LIST_METADATA_SIZE
+                + (self.get_writer_tell() as usize).div_ceil(8)
+                + Self::rank_index_size(storage_size, self.get_hash_bits().unwrap())
+        } else {
+            Self::packed_registers_size()
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of bytes required to serialize the counter.
+    pub fn serialized_size(&self) -> usize {
+        SERIALIZATION_HEADER_SIZE + self.payload_size()
+    }
+
+    /// Serializes the counter into the provided buffer.
+    ///
+    /// # Arguments
+    /// * `bytes` - The buffer where the counter is written, of at least [`Self::serialized_size`] bytes.
+    ///
+    /// # Returns
+    /// The number of bytes written to the buffer.
+    ///
+    /// # Errors
+    /// * If the provided buffer is too small to hold the serialized counter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// hll.extend(0..100);
+    ///
+    /// let mut bytes = [0_u8; 1024];
+    /// let written = hll.to_bytes(&mut bytes).unwrap();
+    /// assert_eq!(written, hll.serialized_size());
+    ///
+    /// let restored: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     HyperLogLog::from_bytes(&bytes[..written]).unwrap();
+    /// assert!(restored.may_contain(&42));
+    /// assert_eq!(restored.estimate_cardinality(), hll.estimate_cardinality());
+    /// ```
+    pub fn to_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        let serialized_size = self.serialized_size();
+        if bytes.len() < serialized_size {
+            return Err(Error::InvalidArgument(
+                "The provided buffer is too small to hold the serialized counter.",
+            ));
+        }
+
+        let (header, payload) = bytes[..serialized_size].split_at_mut(SERIALIZATION_HEADER_SIZE);
+
+        if self.is_hash_list() {
+            self.write_hash_list_payload(payload);
+        } else {
+            self.write_registers_payload(payload);
+        }
+        self.write_header(header, payload);
+
+        Ok(serialized_size)
+    }
+
+    #[inline]
+    /// Writes the header describing the counter and the provided payload.
+    fn write_header(&self, header: &mut [u8], payload: &[u8]) {
+        header[0..4].copy_from_slice(&MAGIC);
+        header[4] = SERIALIZATION_FORMAT_VERSION;
+        header[5] = P::EXPONENT;
+        header[6] = B::NUMBER_OF_BITS;
+        header[7] = if self.is_hash_list() {
+            HASH_LIST_MODE
+        } else {
+            REGISTERS_MODE
+        };
+        header[8..16].copy_from_slice(&self.hasher_identifier().to_le_bytes());
+        header[16..20].copy_from_slice(&u32::try_from(payload.len()).unwrap().to_le_bytes());
+        header[20..24].copy_from_slice(&crc32(payload).to_le_bytes());
+    }
+
+    #[inline]
+    /// Writes the hash list metadata and the used bytes of the hash list.
+    fn write_hash_list_payload(&self, payload: &mut [u8]) {
+        let storage = <R as AsRef<[u8]>>::as_ref(&self.registers);
+        let hash_bits = self.get_hash_bits().unwrap();
+        let number_of_hashes = self.get_number_of_hashes().unwrap();
+        let writer_tell = self.get_writer_tell();
+        let prefix_size = (writer_tell as usize).div_ceil(8);
+        let rank_index_size = Self::rank_index_size(storage.len(), hash_bits);
+
+        payload[0] = hash_bits;
+        payload[1..5].copy_from_slice(&number_of_hashes.to_le_bytes());
+        payload[5..9].copy_from_slice(&self.get_duplicates().to_le_bytes());
+        payload[9..13].copy_from_slice(&writer_tell.to_le_bytes());
+        payload[13..17].copy_from_slice(&u32::try_from(storage.len()).unwrap().to_le_bytes());
+
+        let (prefix, rank_index) = payload[HASH_LIST_METADATA_SIZE..].split_at_mut(prefix_size);
+        prefix.copy_from_slice(&storage[..prefix_size]);
+        GapHash::<P, B>::to_canonical_bytes(prefix, number_of_hashes, hash_bits, writer_tell);
+        rank_index.copy_from_slice(&storage[storage.len() - rank_index_size..]);
+    }
+
+    #[inline]
+    /// Writes the packed registers.
+    fn write_registers_payload(&self, payload: &mut [u8]) {
+        self.registers.write_canonical_bytes(payload);
+    }
+
+    /// Deserializes a counter from the provided bytes, using the provided hasher builder.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the serialized counter, as written by [`Self::to_bytes`].
+    /// * `build_hasher` - The builder of the hashers used to hash the elements.
+    ///
+    /// # Errors
+    /// * If the bytes are truncated or do not start with a valid header.
+    /// * If the format version, the precision, the bits or the hasher do not match.
+    /// * If the checksum does not match the payload.
+    /// * If the payload describes an invalid counter.
+    pub fn from_bytes_with_hasher(bytes: &[u8], build_hasher: H) -> Result<Self, Error> {
+        if bytes.len() < SERIALIZATION_HEADER_SIZE {
+            return Err(Error::CorruptData(
+                "The provided bytes are too short to contain the header.",
+            ));
+        }
+        if bytes[0..4] != MAGIC {
+            return Err(Error::CorruptData(
+                "The provided bytes do not contain a serialized counter.",
+            ));
+        }
+        if bytes[4] != SERIALIZATION_FORMAT_VERSION {
+            return Err(Error::IncompatibleConfiguration(
+                "The serialization format version is not supported.",
+            ));
+        }
+        if bytes[5] != P::EXPONENT {
+            return Err(Error::IncompatibleConfiguration(
+                "The precision of the serialized counter does not match.",
+            ));
+        }
+        if bytes[6] != B::NUMBER_OF_BITS {
+            return Err(Error::IncompatibleConfiguration(
+                "The number of bits of the serialized counter does not match.",
+            ));
+        }
+
+        let mut hll = Self::with_hasher(build_hasher);
+
+        if read_u64(bytes, 8) != hll.hasher_identifier() {
+            return Err(Error::IncompatibleConfiguration(
+                "The hasher of the serialized counter does not match.",
+            ));
+        }
+        let payload = &bytes[SERIALIZATION_HEADER_SIZE..];
+        if read_u32(bytes, 16) as usize != payload.len() {
+            return Err(Error::CorruptData(
+                "The payload length of the serialized counter does not match.",
+            ));
+        }
+        if read_u32(bytes, 20) != crc32(payload) {
+            return Err(Error::CorruptData(
+                "The checksum of the serialized counter does not match.",
+            ));
+        }
+
+        match bytes[7] {
+            HASH_LIST_MODE => hll.read_hash_list_payload(payload)?,
+            REGISTERS_MODE => hll.read_registers_payload(payload)?,
+            _ => {
+                return Err(Error::CorruptData(
+                    "The mode of the serialized counter is not valid.",
+                ))
+            }
+        }
+
+        Ok(hll)
+    }
+
+    #[inline]
+    /// Restores the hash list from the provided payload, validating its metadata and its hashes.
+    fn read_hash_list_payload(&mut self, payload: &[u8]) -> Result<(), Error> {
+        if payload.len() < HASH_LIST_METADATA_SIZE {
+            return Err(Error::CorruptData(
+                "The payload is too short to contain the hash list metadata.",
+            ));
+        }
+        let hash_bits = payload[0];
+        let number_of_hashes = read_u32(payload, 1);
+        let duplicates = read_u32(payload, 5);
+        let writer_tell = read_u32(payload, 9);
+        let storage_size = read_u32(payload, 13) as usize;
+
+        if !(GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS..=GapHash::<P, B>::LARGEST_VIABLE_HASH_BITS)
+            .contains(&hash_bits)
+        {
+            return Err(Error::CorruptData(
+                "The hash bits of the serialized hash list are not viable.",
+            ));
+        }
+        if number_of_hashes > MAXIMAL_NUMBER_OF_HASHES
+            || duplicates > MAXIMAL_DUPLICATES
+            || number_of_hashes > writer_tell
+            || (number_of_hashes == 0 && writer_tell > 0)
+        {
+            return Err(Error::CorruptData(
+                "The metadata of the serialized hash list are not valid.",
+            ));
+        }
+
+        // Registers backed by a vector grow up to the size of the serialized hash list.
+        while <R as AsRef<[u8]>>::as_ref(&self.registers).len() < storage_size
+            && <R as AsRef<[u8]>>::as_ref(&self.registers).len() * 8 < R::bitsize()
+        {
+            self.registers.increase_capacity()?;
+        }
+        let storage = self.registers.as_mut();
+        if storage.len() != storage_size {
+            return Err(Error::CorruptData(
+                "The storage size of the serialized hash list is not valid.",
+            ));
+        }
+
+        let prefix_size = (writer_tell as usize).div_ceil(8);
+        let rank_index_size = Self::rank_index_size(storage_size, hash_bits);
+        if payload.len() != HASH_LIST_METADATA_SIZE + prefix_size + rank_index_size
+            || writer_tell as usize
+                > storage_size * 8
+                    - GapHash::<P, B>::rank_index_total_size(hash_bits) as usize
+                        * usize::from(rank_index_size > 0)
+        {
+            return Err(Error::CorruptData(
+                "The hash list does not fit in the storage of the counter.",
+            ));
+        }
+
+        // Prefix-free encoded hash lists are only ever employed once the storage
+        // has reached its maximal size, which is where their rank index is kept.
+        if GapHash::<P, B>::is_prefix_free_encoded(number_of_hashes, hash_bits, writer_tell)
+            && storage_size != GapHash::<P, B>::maximal_number_of_bytes()
+        {
+            return Err(Error::CorruptData(
+                "The storage size of the serialized hash list is not valid.",
+            ));
+        }
+
+        let (prefix, rank_index) = payload[HASH_LIST_METADATA_SIZE..].split_at(prefix_size);
+        if !GapHash::<P, B>::is_valid_canonical_bytes(
+            prefix,
+            rank_index,
+            number_of_hashes,
+            hash_bits,
+            writer_tell,
+        ) {
+            return Err(Error::CorruptData(
+                "The hashes of the serialized hash list do not match its metadata.",
+            ));
+        }
+        storage[..prefix_size].copy_from_slice(prefix);
+        storage[storage_size - rank_index_size..].copy_from_slice(rank_index);
+        GapHash::<P, B>::from_canonical_bytes(
+            &mut storage[..prefix_size],
+            number_of_hashes,
+            hash_bits,
+            writer_tell,
+        );
+
+        self.set_hash_bits(hash_bits);
+        self.set_number_of_hashes(number_of_hashes);
+        self.set_duplicates(duplicates);
+        self.set_writer_tell(writer_tell);
+
+        Ok(())
+    }
+
+    #[inline]
+    /// Restores the registers from the provided payload, recomputing their harmonic sum.
+    fn read_registers_payload(&mut self, payload: &[u8]) -> Result<(), Error> {
+        if payload.len() != Self::packed_registers_size() {
+            return Err(Error::CorruptData(
+                "The payload length does not match the number of registers.",
+            ));
+        }
+
+        self.saturate_capacity()?;
+        self.registers.read_canonical_bytes(payload);
+
+        let maximal_register = maximal_register_value::<P, B>();
+        let mut harmonic_sum = 0.0;
+        for register in self.registers.iter_registers() {
+            if register > maximal_register {
+                return Err(Error::CorruptData(
+                    "The registers of the serialized counter hold values that cannot be reached.",
+                ));
+            }
+            harmonic_sum += f64::integer_exp2_minus(register);
+        }
+        self.harmonic_sum = harmonic_sum;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    /// Writes the serialized counter to the provided writer.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer where the serialized counter is written.
+    ///
+    /// # Returns
+    /// The number of bytes written.
+    ///
+    /// # Errors
+    /// * If the writer fails to write the serialized counter.
+    pub fn write_bytes<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<usize> {
+        let mut bytes = vec![0; self.serialized_size()];
+        let written = self
+            .to_bytes(&mut bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))?;
+        writer.write_all(&bytes)?;
+        Ok(written)
+    }
+
+    #[cfg(feature = "std")]
+    /// Reads a serialized counter from the provided reader, using the provided hasher builder.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader from which the serialized counter is read.
+    /// * `build_hasher` - The builder of the hashers used to hash the elements.
+    ///
+    /// # Errors
+    /// * If the reader fails to read the serialized counter.
+    /// * If the serialized counter is not valid, as described in [`Self::from_bytes_with_hasher`].
+    pub fn read_bytes_with_hasher<Rd: std::io::Read>(
+        mut reader: Rd,
+        build_hasher: H,
+    ) -> std::io::Result<Self> {
+        let mut bytes = vec![0; SERIALIZATION_HEADER_SIZE];
+        reader.read_exact(&mut bytes)?;
+
+        // We bound the payload length before allocating it, so that corrupted
+        // headers cannot cause arbitrarily large allocations.
+        let payload_size = read_u32(&bytes, 16) as usize;
+        if payload_size > HASH_LIST_METADATA_SIZE + 2 * GapHash::<P, B>::maximal_number_of_bytes() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                Error::CorruptData("The payload length of the serialized counter is not valid."),
+            ));
+        }
+        bytes.resize(SERIALIZATION_HEADER_SIZE + payload_size, 0);
+        reader.read_exact(&mut bytes[SERIALIZATION_HEADER_SIZE..])?;
+
+        Self::from_bytes_with_hasher(&bytes, build_hasher)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> HyperLogLog<P, B, R, H> {
+    #[inline]
+    /// Deserializes a counter from the provided bytes.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the serialized counter, as written by [`Self::to_bytes`].
+    ///
+    /// # Errors
+    /// * If the serialized counter is not valid, as described in [`Self::from_bytes_with_hasher`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_hasher(bytes, H::default())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    /// Reads a serialized counter from the provided reader.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader from which the serialized counter is read.
+    ///
+    /// # Errors
+    /// * If the reader fails to read the serialized counter.
+    /// * If the serialized counter is not valid, as described in [`Self::from_bytes_with_hasher`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision10, Bits5, <Precision10 as PackedRegister<Bits5>>::Vec> =
+    ///     Default::default();
+    /// hll.extend(0..10_000);
+    ///
+    /// let mut bytes = Vec::new();
+    /// hll.write_bytes(&mut bytes).unwrap();
+    ///
+    /// let restored: HyperLogLog<Precision10, Bits5, <Precision10 as PackedRegister<Bits5>>::Vec> =
+    ///     HyperLogLog::read_bytes(bytes.as_slice()).unwrap();
+    /// assert!(restored.may_contain(&42));
+    /// assert_eq!(restored.estimate_cardinality(), hll.estimate_cardinality());
+    /// ```
+    pub fn read_bytes<Rd: std::io::Read>(reader: Rd) -> std::io::Result<Self> {
+        Self::read_bytes_with_hasher(reader, H::default())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_estimator;
+    use twox_hash::XxHash;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test_estimator]
+    fn test_canonical_hash_list<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        hll.extend((0..3_u64).map(|position| splitmix64(splitmix64(position))));
+        let hash_bits = hll.get_hash_bits().unwrap();
+        let number_of_hashes = hll.get_number_of_hashes().unwrap() as usize;
+        assert!(!GapHash::<P, B>::is_prefix_free_encoded(
+            hll.get_number_of_hashes().unwrap(),
+            hash_bits,
+            hll.get_writer_tell()
+        ));
+
+        let mut bytes = vec![0; hll.serialized_size()];
+        hll.to_bytes(&mut bytes).unwrap();
+        let hashes = &bytes[SERIALIZATION_HEADER_SIZE + HASH_LIST_METADATA_SIZE..];
+
+        // The plain hashes are stored in little-endian order, independently of the architecture.
+        let canonical_hashes: Vec<u32> = match hash_bits / 8 {
+            2 => hashes
+                .chunks_exact(2)
+                .take(number_of_hashes)
+                .map(|chunk| u32::from(u16::from_le_bytes([chunk[0], chunk[1]])))
+                .collect(),
+            4 => hashes
+                .chunks_exact(4)
+                .take(number_of_hashes)
+                .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+            _ => return,
+        };
+        assert_eq!(canonical_hashes.len(), number_of_hashes);
+        assert!(canonical_hashes
+            .windows(2)
+            .all(|window| window[0] > window[1]));
+    }
+
+    #[test_estimator]
+    fn test_bytes_round_trip<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        let mut inserted = 0;
+
+        // The empty, the smallest and the largest hash lists, and the first registers.
+        for size in [0, 1, capacity, capacity + 1] {
+            hll.extend((inserted..size).map(element));
+            inserted = size;
+
+            let mut bytes = vec![0; hll.serialized_size()];
+            assert_eq!(hll.to_bytes(&mut bytes).unwrap(), bytes.len());
+            assert!(hll.to_bytes(&mut bytes[1..]).is_err());
+
+            let restored: HyperLogLog<P, B, R, H> = HyperLogLog::from_bytes(&bytes).unwrap();
+            assert_eq!(restored.is_hash_list(), hll.is_hash_list());
+            assert!((0..size).all(|position| restored.may_contain(&element(position))));
+            assert_eq!(
+                restored.estimate_cardinality().to_bits(),
+                hll.estimate_cardinality().to_bits()
+            );
+
+            // Serializing the restored counter must result in the very same bytes.
+            let mut restored_bytes = vec![0; restored.serialized_size()];
+            restored.to_bytes(&mut restored_bytes).unwrap();
+            assert_eq!(restored_bytes, bytes);
+
+            let mut written = Vec::new();
+            assert_eq!(hll.write_bytes(&mut written).unwrap(), bytes.len());
+            assert_eq!(written, bytes);
+            let read: HyperLogLog<P, B, R, H> =
+                HyperLogLog::read_bytes(written.as_slice()).unwrap();
+            let mut read_bytes = vec![0; read.serialized_size()];
+            read.to_bytes(&mut read_bytes).unwrap();
+            assert_eq!(read_bytes, bytes);
+
+            // Truncated or corrupted bytes must be rejected.
+            assert!(HyperLogLog::<P, B, R, H>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+            assert!(HyperLogLog::<P, B, R, H>::read_bytes(&bytes[..bytes.len() - 1]).is_err());
+            for position in [0, 4, 5, 6, 7, 8, 16, 20, bytes.len() - 1] {
+                let mut corrupted = bytes.clone();
+                corrupted[position] ^= 0b1000_0001;
+                assert!(
+                    HyperLogLog::<P, B, R, H>::from_bytes(&corrupted).is_err(),
+                    "The corrupted byte in position {position} must be detected."
+                );
+            }
+        }
+    }
+
+    #[test_estimator]
+    fn test_corrupted_hash_list<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        let mut inserted = 0;
+
+        // The smallest and the largest hash lists.
+        for size in [1, capacity] {
+            hll.extend((inserted..size).map(element));
+            inserted = size;
+            if !hll.is_hash_list() {
+                break;
+            }
+
+            let mut bytes = vec![0; hll.serialized_size()];
+            hll.to_bytes(&mut bytes).unwrap();
+
+            // The corrupted payloads come with a valid checksum, so that
+            // only the validation of the hash list may detect them.
+            let with_checksum = |mut corrupted: Vec<u8>| {
+                let checksum = crc32(&corrupted[SERIALIZATION_HEADER_SIZE..]);
+                corrupted[20..24].copy_from_slice(&checksum.to_le_bytes());
+                corrupted
+            };
+
+            // Metadata disagreeing with the hashes must be rejected.
+            let number_of_hashes = read_u32(&bytes, SERIALIZATION_HEADER_SIZE + 1);
+            for wrong_number_of_hashes in [number_of_hashes.wrapping_sub(1), number_of_hashes + 1] {
+                let mut corrupted = bytes.clone();
+                corrupted[SERIALIZATION_HEADER_SIZE + 1..SERIALIZATION_HEADER_SIZE + 5]
+                    .copy_from_slice(&wrong_number_of_hashes.to_le_bytes());
+                assert!(matches!(
+                    HyperLogLog::<P, B, R, H>::from_bytes(&with_checksum(corrupted)),
+                    Err(Error::CorruptData(_))
+                ));
+            }
+
+            // Corrupted hashes must either be rejected or describe a valid hash list,
+            // which must then be usable without panicking.
+            let hashes_offset = SERIALIZATION_HEADER_SIZE + HASH_LIST_METADATA_SIZE;
+            let hashes_bits = hll.get_writer_tell() as usize;
+            for position in (0..hashes_bits).step_by(hashes_bits.div_ceil(64).max(1)) {
+                let mut corrupted = bytes.clone();
+                corrupted[hashes_offset + position / 8] ^= 1 << (position % 8);
+                match HyperLogLog::<P, B, R, H>::from_bytes(&with_checksum(corrupted)) {
+                    Ok(mut restored) => {
+                        restored.extend((size..size + 100).map(element));
+                        assert!(restored.estimate_cardinality() > 0.0);
+                        restored.try_merge(&hll).unwrap();
+                    }
+                    Err(error) => assert!(matches!(error, Error::CorruptData(_))),
+                }
+            }
+        }
+    }
+
+    #[test_estimator]
+    fn test_corrupted_rank_index<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+
+        // We fill the hash list until it is prefix-free encoded, so that its rank index is in use.
+        let mut inserted = 0;
+        while hll.is_hash_list()
+            && !GapHash::<P, B>::is_prefix_free_encoded(
+                hll.get_number_of_hashes().unwrap(),
+                hll.get_hash_bits().unwrap(),
+                hll.get_writer_tell(),
+            )
+        {
+            hll.insert(&element(inserted));
+            inserted += 1;
+        }
+        if !hll.is_hash_list() {
+            return;
+        }
+        let rank_index_size = HyperLogLog::<P, B, R, H>::rank_index_size(
+            <R as AsRef<[u8]>>::as_ref(&hll.registers).len(),
+            hll.get_hash_bits().unwrap(),
+        );
+        if rank_index_size == 0 {
+            return;
+        }
+
+        let mut bytes = Vec::new();
+        hll.write_bytes(&mut bytes).unwrap();
+
+        // Corrupted rank indices, even with a valid checksum, must either be rejected
+        // or describe a valid rank index, which must then be usable without panicking.
+        let rank_index_bits = rank_index_size * 8;
+        let rank_index_offset = bytes.len() - rank_index_size;
+        for position in (0..rank_index_bits).step_by(rank_index_bits.div_ceil(64)) {
+            let mut corrupted = bytes.clone();
+            corrupted[rank_index_offset + position / 8] ^= 1 << (position % 8);
+            let checksum = crc32(&corrupted[SERIALIZATION_HEADER_SIZE..]);
+            corrupted[20..24].copy_from_slice(&checksum.to_le_bytes());
+
+            match HyperLogLog::<P, B, R, H>::read_bytes(corrupted.as_slice()) {
+                Ok(mut restored) => {
+                    restored.extend((inserted..inserted + 100).map(element));
+                    assert!((0..inserted + 100)
+                        .all(|position| restored.may_contain(&element(position))));
+                    restored.try_merge(&hll).unwrap();
+                }
+                Err(error) => assert_eq!(error.kind(), std::io::ErrorKind::InvalidData),
+            }
+        }
+    }
+
+    #[test]
+    fn test_unreachable_registers() {
+        let mut hll: HyperLogLog<Precision4, Bits6, <Precision4 as PackedRegister<Bits6>>::Array> =
+            HyperLogLog::default();
+        hll.extend(0..1_000_u64);
+        assert!(!hll.is_hash_list());
+
+        let mut bytes = vec![0; hll.serialized_size()];
+        hll.to_bytes(&mut bytes).unwrap();
+
+        // The first register is set to the largest value fitting in six bits, which no
+        // hash may produce with precision 4, and the checksum is updated accordingly.
+        bytes[SERIALIZATION_HEADER_SIZE] |= 0b1111_1100;
+        let checksum = crc32(&bytes[SERIALIZATION_HEADER_SIZE..]);
+        bytes[20..24].copy_from_slice(&checksum.to_le_bytes());
+
+        assert!(matches!(
+            HyperLogLog::<Precision4, Bits6, <Precision4 as PackedRegister<Bits6>>::Array>::from_bytes(&bytes),
+            Err(Error::CorruptData(_))
+        ));
+    }
+}
@@ -37,6 +37,7 @@ mod test_composite_hash {
     #[allow(unsafe_code)]
     #[cfg(feature = "std")]
     fn test_composite_hash<P: Precision + PackedRegister<B>, B: Bits>() {
+        let counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
         let mut random_state = 498_123_456_789;
         let number_of_iterations = core::cmp::min(1, 10_000 / (1 << (P::EXPONENT - 4)));
 
@@ -93,13 +94,8 @@ mod test_composite_hash {
                     );
                 }
 
-                let (index, register, original_hash) = <HyperLogLog<
-                    P,
-                    B,
-                    <P as PackedRegister<B>>::Array,
-                >>::index_and_register_and_hash(
-                    &random_value
-                );
+                let (index, register, original_hash) =
+                    counter.index_and_register_and_hash(&random_value);
 
                 let mut reference_encoded_hash =
                     GapHash::<P, B>::encode(index, register, original_hash, hash_bits);
@@ -237,18 +233,14 @@ mod test_composite_hash {
     #[allow(unsafe_code)]
     fn test_composite_hash_stateless_operations<P: Precision + PackedRegister<B>, B: Bits>() {
         const NUMBER_OF_HASHES: usize = 1_000;
+        let counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
         // We start from the maximal number of bits for the hash.
         for hash_bits in
             GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS..=GapHash::<P, B>::LARGEST_VIABLE_HASH_BITS
         {
             for random_value in iter_random_values::<u64>(NUMBER_OF_HASHES as u64, None, None) {
-                let (index, register, original_hash) = <HyperLogLog<
-                    P,
-                    B,
-                    <P as PackedRegister<B>>::Array,
-                >>::index_and_register_and_hash(
-                    &random_value
-                );
+                let (index, register, original_hash) =
+                    counter.index_and_register_and_hash(&random_value);
 
                 let encoded_hash =
                     GapHash::<P, B>::encode(index, register, original_hash, hash_bits);
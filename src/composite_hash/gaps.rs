@@ -9,7 +9,7 @@ use super::{
     Debug, LastBufferedBit, Precision, SaturationError, SwitchHash,
 };
 use crate::bits::Bits;
-use bitreader::{len_rice, BitReader};
+use bitreader::{len_rice, BitReader, CheckedBitReader};
 use bitwriter::BitWriter;
 use core::mem::size_of;
 use optimal_codes::OPTIMAL_RICE_COEFFICIENTS;
@@ -600,22 +600,312 @@ impl<P: Precision, B: Bits> GapHash<P, B> {
     }
 
     #[inline]
-    fn b(hash_bits: u8) -> (u8, u8) {
-        let data =
-            OPTIMAL_RICE_COEFFICIENTS[P::EXPONENT as usize - 4][B::NUMBER_OF_BITS as usize - 4];
+    /// Converts the provided in-memory hash list into its canonical, endian-independent layout.
+    ///
+    /// # Arguments
+    /// * `hashes` - The bytes of the hash list, starting from its first hash, converted in place.
+    /// * `number_of_hashes` - The number of hashes in the hash list.
+    /// * `hash_bits` - The number of bits used to encode the hashes.
+    /// * `bit_index` - The bit index of the writer of the hash list.
+    ///
+    /// # Implementative details
+    /// The prefix-free encoded hashes and the rank index are written as big-endian words,
+    /// and as such are already endian-independent, as are the hashes stored in one or three
+    /// bytes. The hashes stored in two or four bytes are instead stored in native-endian
+    /// order, and are converted to little-endian order.
+    ///
+    /// # Panics
+    /// If the provided bytes are fewer than the bytes of the hashes.
+    pub fn to_canonical_bytes(
+        hashes: &mut [u8],
+        number_of_hashes: u32,
+        hash_bits: u8,
+        bit_index: u32,
+    ) {
+        Self::convert_plain_hashes(hashes, number_of_hashes, hash_bits, bit_index);
+    }
 
-        for (target_hash_bits, uniform) in data {
-            if *target_hash_bits == hash_bits {
-                return (*target_hash_bits, *uniform);
+    #[inline]
+    /// Converts the provided canonical hash list, as returned by [`Self::to_canonical_bytes`],
+    /// back into its in-memory layout.
+    ///
+    /// # Arguments
+    /// * `hashes` - The bytes of the hash list, starting from its first hash, converted in place.
+    /// * `number_of_hashes` - The number of hashes in the hash list.
+    /// * `hash_bits` - The number of bits used to encode the hashes.
+    /// * `bit_index` - The bit index of the writer of the hash list.
+    ///
+    /// # Panics
+    /// If the provided bytes are fewer than the bytes of the hashes.
+    pub fn from_canonical_bytes(
+        hashes: &mut [u8],
+        number_of_hashes: u32,
+        hash_bits: u8,
+        bit_index: u32,
+    ) {
+        Self::convert_plain_hashes(hashes, number_of_hashes, hash_bits, bit_index);
+    }
+
+    #[inline]
+    /// Swaps the hashes stored in two or four bytes between native-endian and little-endian order.
+    ///
+    /// # Implementative details
+    /// On little-endian architectures this is a no-op, while on big-endian ones it reverses the
+    /// bytes of each hash. Since the swap is its own inverse, it converts in both directions.
+    fn convert_plain_hashes(
+        hashes: &mut [u8],
+        number_of_hashes: u32,
+        hash_bits: u8,
+        bit_index: u32,
+    ) {
+        if Self::is_prefix_free_encoded(number_of_hashes, hash_bits, bit_index) {
+            return;
+        }
+        let hash_bytes = usize::from(hash_bits / 8);
+        let hashes = &mut hashes[..number_of_hashes as usize * hash_bytes];
+        match hash_bytes {
+            2 => hashes.chunks_exact_mut(2).for_each(|chunk| {
+                let hash = u16::from_ne_bytes([chunk[0], chunk[1]]);
+                chunk.copy_from_slice(&hash.to_le_bytes());
+            }),
+            4 => hashes.chunks_exact_mut(4).for_each(|chunk| {
+                let hash = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                chunk.copy_from_slice(&hash.to_le_bytes());
+            }),
+            _ => {}
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns whether the provided canonical hash list, as returned by [`Self::to_canonical_bytes`],
+    /// is consistent with the provided metadata.
+    ///
+    /// # Arguments
+    /// * `hashes` - The canonical bytes of the hash list, starting from its first hash.
+    /// * `rank_index` - The bytes at the end of the storage holding the rank index, if any.
+    /// * `number_of_hashes` - The number of hashes in the hash list.
+    /// * `hash_bits` - The number of bits used to encode the hashes.
+    /// * `bit_index` - The bit index of the writer of the hash list.
+    ///
+    /// # Implementative details
+    /// Hash lists that are not prefix-free encoded must use exactly `hash_bits` bits per hash,
+    /// while prefix-free encoded ones must employ hash bits with an optimal Rice coefficient.
+    /// The hash list is then decoded once, checking that it contains exactly `number_of_hashes`
+    /// valid hashes sorted in strictly decreasing order, that the gaps between them are exactly
+    /// the ones that would be written for them, and that the last one ends at `bit_index`.
+    /// When the prefix-free encoded hash list employs a rank index, the entry of each bucket
+    /// must point to the first hash of the bucket, and the entries of empty buckets must be
+    /// uninitialized.
+    pub fn is_valid_canonical_bytes(
+        hashes: &[u8],
+        rank_index: &[u8],
+        number_of_hashes: u32,
+        hash_bits: u8,
+        bit_index: u32,
+    ) -> bool {
+        if !(Self::SMALLEST_VIABLE_HASH_BITS..=Self::LARGEST_VIABLE_HASH_BITS).contains(&hash_bits)
+            || u64::from(number_of_hashes) * u64::from(hash_bits) > u64::from(u32::MAX)
+        {
+            return false;
+        }
+
+        if Self::is_prefix_free_encoded(number_of_hashes, hash_bits, bit_index) {
+            Self::optimal_rice_coefficient(hash_bits).is_some_and(|uniform_coefficient| {
+                Self::is_valid_prefix_free_encoding(
+                    hashes,
+                    rank_index,
+                    number_of_hashes,
+                    hash_bits,
+                    bit_index,
+                    uniform_coefficient,
+                )
+            })
+        } else {
+            bit_index == number_of_hashes * u32::from(hash_bits)
+                && Self::is_valid_plain_encoding(hashes, number_of_hashes, hash_bits)
+        }
+    }
+
+    #[inline]
+    /// Returns whether the provided canonical plain hashes are valid and strictly decreasing.
+    fn is_valid_plain_encoding(hashes: &[u8], number_of_hashes: u32, hash_bits: u8) -> bool {
+        let hash_bytes = usize::from(hash_bits / 8);
+        let Some(hashes) = hashes.get(..number_of_hashes as usize * hash_bytes) else {
+            return false;
+        };
+        let mut previous_hash: Option<u32> = None;
+        hashes.chunks_exact(hash_bytes).all(|chunk| {
+            let mut bytes = [0; 4];
+            bytes[..hash_bytes].copy_from_slice(chunk);
+            let hash = u32::from_le_bytes(bytes);
+            let is_valid = previous_hash.is_none_or(|previous_hash| hash < previous_hash)
+                && SwitchHash::<P, B>::canonical_register(hash, hash_bits).is_some();
+            previous_hash = Some(hash);
+            is_valid
+        })
+    }
+
+    #[inline]
+    /// Returns whether the provided prefix-free encoded hashes are valid and strictly decreasing,
+    /// and whether the rank index, when employed, is consistent with them.
+    fn is_valid_prefix_free_encoding(
+        hashes: &[u8],
+        rank_index: &[u8],
+        number_of_hashes: u32,
+        hash_bits: u8,
+        bit_index: u32,
+        uniform_coefficient: u8,
+    ) -> bool {
+        let mut reader = CheckedBitReader::new(hashes, bit_index);
+        let mut entries = if Self::has_rank_index() {
+            let Ok(rank_index_bits) = u32::try_from(rank_index.len() * 8) else {
+                return false;
+            };
+            let Some(offset) = rank_index_bits.checked_sub(Self::rank_index_total_size(hash_bits))
+            else {
+                return false;
+            };
+            Some(CheckedBitReader::skip(rank_index, offset, rank_index_bits))
+        } else {
+            None
+        };
+        let mut next_bucket = 1;
+        let mut previous_hash: Option<u32> = None;
+        let mut decoded_hashes = 0;
+
+        while reader.position() < bit_index {
+            let position = reader.position();
+            let hash = if let Some(previous_hash) = previous_hash {
+                let Some(hash) =
+                    Self::read_gap(&mut reader, previous_hash, hash_bits, uniform_coefficient)
+                else {
+                    return false;
+                };
+                hash
+            } else {
+                let Some(hash) = reader.read_bits(hash_bits) else {
+                    return false;
+                };
+                if SwitchHash::<P, B>::canonical_register(hash, hash_bits).is_none() {
+                    return false;
+                }
+                hash
+            };
+            previous_hash = Some(hash);
+            decoded_hashes += 1;
+            if decoded_hashes > number_of_hashes {
+                return false;
+            }
+
+            // Since the hashes are sorted decreasingly, the first hash of each bucket is
+            // the first one we decode, and all of the buckets skipped so far are empty.
+            if let Some(entries) = entries.as_mut() {
+                let bucket = Self::rank_index_hash_bucket(hash_bits, hash);
+                while next_bucket <= bucket {
+                    let Some(entry) = Self::read_rank_index_entry(entries, hash_bits) else {
+                        return false;
+                    };
+                    let uninitialized = entry == (Self::rank_index_mask(), 0);
+                    let is_valid = if next_bucket < bucket {
+                        uninitialized
+                    } else {
+                        // The first hash of the list is stored explicitly, and as
+                        // such its bucket does not need to be initialized.
+                        entry == (position, hash) || (position == 0 && uninitialized)
+                    };
+                    if !is_valid {
+                        return false;
+                    }
+                    next_bucket += 1;
+                }
+            }
+        }
+
+        if let Some(entries) = entries.as_mut() {
+            while next_bucket < Self::rank_index_capacity() {
+                if Self::read_rank_index_entry(entries, hash_bits)
+                    != Some((Self::rank_index_mask(), 0))
+                {
+                    return false;
+                }
+                next_bucket += 1;
             }
         }
 
-        unreachable!("The hash bits ({hash_bits}) must be one of the optimal hash bits.",);
+        decoded_hashes == number_of_hashes
+    }
+
+    #[inline]
+    /// Reads the bit index and the hash of the next entry of the rank index.
+    fn read_rank_index_entry(
+        entries: &mut CheckedBitReader<'_>,
+        hash_bits: u8,
+    ) -> Option<(u32, u32)> {
+        Some((
+            entries.read_bits(Self::rank_index_bits())?,
+            entries.read_bits(hash_bits)?,
+        ))
+    }
+
+    #[inline]
+    /// Reads the gap following the provided hash, returning the hash it leads to if it is
+    /// a valid hash smaller than the previous one, encoded with the expected gap.
+    fn read_gap(
+        reader: &mut CheckedBitReader<'_>,
+        previous_hash: u32,
+        hash_bits: u8,
+        uniform_coefficient: u8,
+    ) -> Option<u32> {
+        let gap = GapFragment {
+            uniform_delta: reader.read_rice(uniform_coefficient)?,
+            geometric_minus_one: u8::try_from(reader.read_unary()?).ok()?,
+        };
+        let register = gap.geometric_minus_one.checked_add(1)?;
+        let previous_fragment = SwitchHash::<P, B>::scompose_hash(previous_hash, hash_bits);
+
+        let hash = if P::EXPONENT + B::NUMBER_OF_BITS == hash_bits {
+            let index = previous_fragment.index.checked_sub(gap.uniform_delta)?;
+            SwitchHash::<P, B>::compose_hash(index, register, 0, hash_bits)
+        } else {
+            let previous_uniform = previous_fragment.uniform(hash_bits);
+            let uniform = if gap.uniform_delta & 1 == 0 {
+                previous_uniform.checked_add(gap.uniform_delta >> 1)?
+            } else {
+                previous_uniform.checked_sub((gap.uniform_delta >> 1) + 1)?
+            };
+            if uniform >> (hash_bits - 1) != 0 {
+                return None;
+            }
+            let remainder_size = hash_bits - 1 - P::EXPONENT;
+            SwitchHash::<P, B>::compose_hash(
+                uniform >> remainder_size,
+                register,
+                uniform & ((1 << remainder_size) - 1),
+                hash_bits,
+            )
+        };
+
+        (hash < previous_hash
+            && SwitchHash::<P, B>::canonical_register(hash, hash_bits) == Some(register)
+            && Self::into_gap_fragment(previous_hash, hash, hash_bits) == gap)
+            .then_some(hash)
+    }
+
+    #[inline]
+    /// Returns the optimal Rice coefficient for the provided hash bits, if they are one of the optimal hash bits.
+    fn optimal_rice_coefficient(hash_bits: u8) -> Option<u8> {
+        OPTIMAL_RICE_COEFFICIENTS[P::EXPONENT as usize - 4][B::NUMBER_OF_BITS as usize - 4]
+            .iter()
+            .find(|(target_hash_bits, _)| *target_hash_bits == hash_bits)
+            .map(|(_, uniform)| *uniform)
     }
 
     #[inline]
     fn uniform_coefficient(hash_bits: u8) -> u8 {
-        Self::b(hash_bits).1
+        Self::optimal_rice_coefficient(hash_bits).unwrap_or_else(|| {
+            unreachable!("The hash bits ({hash_bits}) must be one of the optimal hash bits.")
+        })
     }
 }
 
@@ -692,6 +982,20 @@ impl<P: Precision, B: Bits> GapHash<P, B> {
         SwitchHash::<P, B>::decode(hash, hash_bits)
     }
 
+    #[must_use]
+    #[inline]
+    /// Returns a hash with the provided index and register value.
+    pub(crate) fn restore_from_index_and_register(index: usize, register: u8) -> u64 {
+        SwitchHash::<P, B>::restore_from_index_and_register(index, register)
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns a hash that, once encoded with the provided hash bits, results in the provided hash.
+    pub(crate) fn restore(hash: u32, hash_bits: u8) -> u64 {
+        SwitchHash::<P, B>::restore(hash, hash_bits)
+    }
+
     #[inline(always)]
     #[must_use]
     /// Downgrade the hash into a smaller hash.
@@ -1088,6 +1392,21 @@ impl<P: Precision, B: Bits> GapHash<P, B> {
     /// The largest viable hash bits that can be employed.
     pub const LARGEST_VIABLE_HASH_BITS: u8 = SwitchHash::<P, B>::LARGEST_VIABLE_HASH_BITS;
 
+    #[inline]
+    #[must_use]
+    /// Returns the largest viable hash bits not exceeding the provided hash bits, if any.
+    pub(crate) fn largest_viable_hash_bits(hash_bits: u8) -> Option<u8> {
+        if hash_bits >= Self::LARGEST_VIABLE_HASH_BITS {
+            return Some(Self::LARGEST_VIABLE_HASH_BITS);
+        }
+
+        OPTIMAL_RICE_COEFFICIENTS[P::EXPONENT as usize - 4][B::NUMBER_OF_BITS as usize - 4]
+            .iter()
+            .rev()
+            .map(|&(target_hash_bits, _)| target_hash_bits)
+            .find(|&target_hash_bits| target_hash_bits <= hash_bits)
+    }
+
     #[inline]
     #[allow(unsafe_code)]
     /// Downgrade the hash into a smaller hash in place.
@@ -1461,8 +1780,286 @@ impl<P: Precision, B: Bits> GapHash<P, B> {
             bit_index: writer_tell,
         })
     }
+
+    #[inline]
+    /// Returns the number of bytes the hash list occupies once it has reached its maximal capacity.
+    pub(crate) const fn maximal_number_of_bytes() -> usize {
+        ((1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE).div_ceil(64) * 8
+    }
+
+    #[inline]
+    /// Writes the provided decreasingly sorted hashes as a new hash list.
+    ///
+    /// # Arguments
+    /// * `hashes` - The slice where the hash list is to be written, overwriting its content.
+    /// * `sorted_hashes` - Closure returning an iterator over the decreasingly sorted unique hashes.
+    /// * `number_of_hashes` - The number of hashes returned by the iterator.
+    /// * `hash_bits` - The number of bits used to encode the provided hashes.
+    ///
+    /// # Implementative details
+    /// When the provided hashes use the largest viable hash bits and they fit in the slice,
+    /// they are stored as they are. Otherwise, we search for the largest hash bits whose
+    /// prefix-free encoding fits in the slice, downgrading the hashes and counting those
+    /// that collide as duplicates. The sizes of the encodings with all of the hash bits are
+    /// computed in a single pass, so that the closure is called at most four times.
+    ///
+    /// # Errors
+    /// * `SaturationError::ExtendableSaturation` if the hashes do not fit but the slice can still be extended.
+    /// * `SaturationError::Saturation` if the hashes do not fit with any of the viable hash bits.
+    pub(crate) fn rebuild<I: Iterator<Item = u32>>(
+        hashes: &mut [u8],
+        sorted_hashes: impl Fn() -> I,
+        number_of_hashes: u32,
+        hash_bits: u8,
+    ) -> Result<InsertMetadata, SaturationError> {
+        if hash_bits == Self::LARGEST_VIABLE_HASH_BITS {
+            if let Some(metadata) =
+                Self::write_plain_hashes(hashes, sorted_hashes(), number_of_hashes, hash_bits)
+            {
+                return Ok(metadata);
+            }
+        }
+
+        // The prefix-free encoded hash list is only ever employed once the underlying
+        // storage has reached its maximal size.
+        if hashes.len() < Self::maximal_number_of_bytes() {
+            return Err(SaturationError::ExtendableSaturation);
+        }
+
+        let Some((metadata, uniform_coefficient)) =
+            Self::largest_fitting_encoding(hashes, sorted_hashes(), number_of_hashes, hash_bits)
+        else {
+            return Err(SaturationError::Saturation(0));
+        };
+
+        if Self::has_rank_index() {
+            Self::initialize_rank_index(hashes, metadata.hash_bits);
+        }
+
+        let bit_index = Self::write_prefix_free_hashes(
+            hashes,
+            sorted_hashes(),
+            hash_bits,
+            metadata.hash_bits,
+            uniform_coefficient,
+        );
+        debug_assert_eq!(bit_index, metadata.bit_index);
+
+        if Self::has_rank_index() {
+            // We register in the rank index the first hash of each bucket, alongside
+            // the bit index where the gap leading to it starts.
+            let target_hash_bits = metadata.hash_bits;
+            let shift = hash_bits - target_hash_bits;
+            let mut position = 0;
+            let mut previous_hash: Option<u32> = None;
+            for hash in sorted_hashes() {
+                let hash = Self::downgrade(hash, hash_bits, shift);
+                if previous_hash == Some(hash) {
+                    continue;
+                }
+                if let Some(previous_hash) = previous_hash {
+                    if Self::rank_index_hash_bucket(target_hash_bits, hash)
+                        > Self::rank_index_hash_bucket(target_hash_bits, previous_hash)
+                    {
+                        Self::update_rank_index(hashes, target_hash_bits, position, hash);
+                    }
+                    let gap = Self::into_gap_fragment(previous_hash, hash, target_hash_bits);
+                    position += len_rice(
+                        gap.uniform_delta,
+                        uniform_coefficient,
+                        gap.geometric_minus_one,
+                    );
+                } else {
+                    position = u32::from(target_hash_bits);
+                }
+                previous_hash = Some(hash);
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    #[inline]
+    /// Writes the provided hashes as they are, if they fit in the slice.
+    fn write_plain_hashes(
+        hashes: &mut [u8],
+        sorted_hashes: impl Iterator<Item = u32>,
+        number_of_hashes: u32,
+        hash_bits: u8,
+    ) -> Option<InsertMetadata> {
+        let hash_bytes = usize::from(hash_bits / 8);
+        let plain_hashes = Self::skip_rank_index(&mut *hashes, hash_bits);
+        if plain_hashes.len() < usize::try_from(number_of_hashes).unwrap() * hash_bytes {
+            return None;
+        }
+        for (hash, target) in sorted_hashes.zip(plain_hashes.chunks_exact_mut(hash_bytes)) {
+            match hash_bytes {
+                1 => target[0] = u8::try_from(hash).unwrap(),
+                2 => target.copy_from_slice(&u16::try_from(hash).unwrap().to_ne_bytes()),
+                3 => target.copy_from_slice(&hash.to_le_bytes()[..3]),
+                4 => target.copy_from_slice(&hash.to_ne_bytes()),
+                _ => unreachable!(),
+            }
+        }
+        Some(InsertMetadata {
+            hash_bits,
+            duplicates: 0,
+            bit_index: number_of_hashes * u32::from(hash_bits),
+        })
+    }
+
+    #[inline]
+    /// Returns the metadata and the uniform coefficient of the prefix-free encoding of the
+    /// provided hashes with the largest hash bits that fits in the slice, if any.
+    ///
+    /// # Implementative details
+    /// The size of the encoding with each of the hash bits not larger than the provided
+    /// ones is computed in a single pass over the hashes, stopping early once none fits.
+    fn largest_fitting_encoding(
+        hashes: &[u8],
+        sorted_hashes: impl Iterator<Item = u32>,
+        number_of_hashes: u32,
+        hash_bits: u8,
+    ) -> Option<(InsertMetadata, u8)> {
+        let data =
+            OPTIMAL_RICE_COEFFICIENTS[P::EXPONENT as usize - 4][B::NUMBER_OF_BITS as usize - 4];
+        debug_assert!(data.len() <= MAXIMAL_NUMBER_OF_ENCODINGS);
+
+        let mut encodings = [EncodingSize::default(); MAXIMAL_NUMBER_OF_ENCODINGS];
+        for (&(target_hash_bits, _), encoding) in data.iter().zip(encodings.iter_mut()) {
+            encoding.fits = target_hash_bits <= hash_bits;
+        }
+
+        for hash in sorted_hashes {
+            let mut any_fits = false;
+            for (&(target_hash_bits, uniform_coefficient), encoding) in
+                data.iter().zip(encodings.iter_mut())
+            {
+                if !encoding.fits {
+                    continue;
+                }
+                let hash = Self::downgrade(hash, hash_bits, hash_bits - target_hash_bits);
+                if encoding.previous_hash == Some(hash) {
+                    encoding.duplicates += 1;
+                    any_fits = true;
+                    continue;
+                }
+                encoding.bit_index += encoding.previous_hash.map_or(
+                    u32::from(target_hash_bits),
+                    |previous_hash| {
+                        let gap = Self::into_gap_fragment(previous_hash, hash, target_hash_bits);
+                        len_rice(
+                            gap.uniform_delta,
+                            uniform_coefficient,
+                            gap.geometric_minus_one,
+                        )
+                    },
+                );
+                encoding.previous_hash = Some(hash);
+                encoding.fits =
+                    encoding.bit_index <= Self::rank_index_offset(hashes, target_hash_bits);
+                any_fits |= encoding.fits;
+            }
+            if !any_fits {
+                return None;
+            }
+        }
+
+        // Other than fitting in the slice, the encoded hashes must also be recognizable
+        // as prefix-free encoded, which is not the case when they are not any smaller
+        // than the explicit hashes.
+        data.iter()
+            .zip(encodings)
+            .rev()
+            .find(|(&(target_hash_bits, _), encoding)| {
+                encoding.fits
+                    && Self::is_prefix_free_encoded(
+                        number_of_hashes - encoding.duplicates,
+                        target_hash_bits,
+                        encoding.bit_index,
+                    )
+            })
+            .map(|(&(target_hash_bits, uniform_coefficient), encoding)| {
+                (
+                    InsertMetadata {
+                        hash_bits: target_hash_bits,
+                        duplicates: encoding.duplicates,
+                        bit_index: encoding.bit_index,
+                    },
+                    uniform_coefficient,
+                )
+            })
+    }
+
+    #[inline]
+    #[allow(unsafe_code)]
+    /// Writes the prefix-free encoding of the provided hashes, downgraded to the target hash bits,
+    /// returning the number of bits written.
+    ///
+    /// # Panics
+    /// * If the slice is not aligned to, or is not a multiple of, the size of an `u64`.
+    fn write_prefix_free_hashes(
+        hashes: &mut [u8],
+        sorted_hashes: impl Iterator<Item = u32>,
+        hash_bits: u8,
+        target_hash_bits: u8,
+        uniform_coefficient: u8,
+    ) -> u32 {
+        // safe because the slice is originally allocated as u64s, as checked below
+        assert!(
+            hashes.as_ptr().align_offset(align_of::<u64>()) == 0,
+            "The hash list must be aligned to u64."
+        );
+        assert!(
+            hashes.len().is_multiple_of(size_of::<u64>()),
+            "The hash list length must be a multiple of the size of u64."
+        );
+        let hashes64 = unsafe {
+            core::slice::from_raw_parts_mut(
+                hashes.as_mut_ptr().cast::<u64>(),
+                hashes.len() / size_of::<u64>(),
+            )
+        };
+        let shift = hash_bits - target_hash_bits;
+        let mut writer = BitWriter::new(hashes64);
+        let mut previous_hash: Option<u32> = None;
+        for hash in sorted_hashes {
+            let hash = Self::downgrade(hash, hash_bits, shift);
+            if previous_hash == Some(hash) {
+                continue;
+            }
+            if let Some(previous_hash) = previous_hash {
+                let gap = Self::into_gap_fragment(previous_hash, hash, target_hash_bits);
+                writer.write_rice(
+                    gap.uniform_delta,
+                    gap.geometric_minus_one,
+                    uniform_coefficient,
+                );
+            } else {
+                writer.write_bits(hash, target_hash_bits);
+            }
+            previous_hash = Some(hash);
+        }
+        writer.tell()
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+/// The size of the prefix-free encoding of a hash list with some hash bits, while it is computed.
+struct EncodingSize {
+    /// The last hash encoded, downgraded to the hash bits of the encoding.
+    previous_hash: Option<u32>,
+    /// The number of bits of the encoding so far.
+    bit_index: u32,
+    /// The number of hashes colliding with the previous one once downgraded.
+    duplicates: u32,
+    /// Whether the encoding still fits in the slice.
+    fits: bool,
 }
 
+/// The maximal number of hash bits considered for the prefix-free encoding of a hash list.
+const MAXIMAL_NUMBER_OF_ENCODINGS: usize = 16;
+
 #[derive(Debug)]
 /// Iterator over downgraded hashes.
 pub enum DispatchedDowngradedIter<'a, P: Precision, B: Bits> {
@@ -1712,6 +2309,11 @@ impl<'a, P: Precision, B: Bits> Iterator for PrefixCodeIter<'a, P, B> {
         if self.first {
             self.first = false;
 
+            // An empty stream, such as the one of an empty hash list, contains no hashes.
+            if self.maximal_bit_index == 0 {
+                return None;
+            }
+
             if self.bitstream.last_read_bit_position() == 0 {
                 self.previous = self.bitstream.read_bits(self.hash_bits) as u32;
             } else {
@@ -2043,6 +2645,7 @@ mod tests {
         P: PackedRegister<B>,
     {
         const NUMBER_OF_HASHES: usize = 1_000;
+        let counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
         let mut first_random_state = 4_575_763_274_578_236u64;
         let mut second_random_state = 564_655_678_565_685_654u64;
         let mut third_random_state = 324_587_447_578_236u64;
@@ -2068,11 +2671,7 @@ mod tests {
                     ))
             {
                 let (first_index, first_register, first_original_hash) =
-                    <HyperLogLog<
-                        P,
-                        B,
-                        <P as PackedRegister<B>>::Array,
-                    >>::index_and_register_and_hash(&first);
+                    counter.index_and_register_and_hash(&first);
 
                 let first_encoded_hash = GapHash::<P, B>::encode(
                     first_index,
@@ -2082,11 +2681,7 @@ mod tests {
                 );
 
                 let (second_index, second_register, second_original_hash) =
-                    <HyperLogLog<
-                        P,
-                        B,
-                        <P as PackedRegister<B>>::Array,
-                    >>::index_and_register_and_hash(&second);
+                    counter.index_and_register_and_hash(&second);
 
                 let second_encoded_hash = GapHash::<P, B>::encode(
                     second_index,
@@ -2096,11 +2691,7 @@ mod tests {
                 );
 
                 let (third_index, third_register, third_original_hash) =
-                    <HyperLogLog<
-                        P,
-                        B,
-                        <P as PackedRegister<B>>::Array,
-                    >>::index_and_register_and_hash(&third);
+                    counter.index_and_register_and_hash(&third);
 
                 let third_encoded_hash = GapHash::<P, B>::encode(
                     third_index,
@@ -109,6 +109,79 @@ impl<'a> BitReader<'a> {
     }
 }
 
+#[derive(Debug, Clone)]
+/// Bit reader over big-endian bytes which, instead of panicking, returns `None`
+/// when asked to read past the provided end of the stream.
+pub(super) struct CheckedBitReader<'a> {
+    data: &'a [u8],
+    position: u32,
+    end: u32,
+}
+
+impl<'a> CheckedBitReader<'a> {
+    #[inline]
+    pub fn new(data: &'a [u8], end: u32) -> Self {
+        Self {
+            data,
+            position: 0,
+            end,
+        }
+    }
+
+    #[inline]
+    /// Creates a new `CheckedBitReader` starting from the bit in the provided position.
+    pub fn skip(data: &'a [u8], position: u32, end: u32) -> Self {
+        Self {
+            data,
+            position,
+            end,
+        }
+    }
+
+    #[inline]
+    /// Returns the position of the next bit to be read.
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    #[inline]
+    fn read_bit(&mut self) -> Option<bool> {
+        if self.position >= self.end {
+            return None;
+        }
+        let byte = self.data.get(self.position as usize / 8)?;
+        let bit = (byte >> (7 - self.position % 8)) & 1 == 1;
+        self.position += 1;
+        Some(bit)
+    }
+
+    #[inline]
+    pub fn read_bits(&mut self, n_bits: u8) -> Option<u32> {
+        debug_assert!(n_bits <= 32);
+        let mut result: u64 = 0;
+        for _ in 0..n_bits {
+            result = (result << 1) | u64::from(self.read_bit()?);
+        }
+        u32::try_from(result).ok()
+    }
+
+    #[inline]
+    pub fn read_unary(&mut self) -> Option<u32> {
+        let mut zeros = 0;
+        while !self.read_bit()? {
+            zeros += 1;
+        }
+        Some(zeros)
+    }
+
+    #[inline]
+    pub fn read_rice(&mut self, b: u8) -> Option<u32> {
+        let quotient = u64::from(self.read_unary()?);
+        let remainder = u64::from(self.read_bits(b)?);
+        u32::try_from((quotient << b) | remainder).ok()
+    }
+}
+
 #[inline]
 /// Returns the number of bits required to encode a given value using a Rice code.
 ///
@@ -5,6 +5,7 @@ use core::slice::Iter;
 use super::gaps::InsertMetadata;
 use super::{LastBufferedBit, SaturationError};
 use crate::composite_hash::GapHash;
+use crate::hyperloglog::maximal_register_value;
 use crate::{bits::Bits, prelude::Precision};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -162,11 +163,9 @@ mod test_compose_scompose_hash {
     where
         P: PackedRegister<B>,
     {
+        let counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
         for value in iter_random_values::<u64>(10_000, None, None) {
-            let (index, register, original_hash) =
-                <HyperLogLog<P, B, <P as PackedRegister<B>>::Array>>::index_and_register_and_hash(
-                    &value,
-                );
+            let (index, register, original_hash) = counter.index_and_register_and_hash(&value);
             for hash_bits in SwitchHash::<P, B>::SMALLEST_VIABLE_HASH_BITS
                 ..=SwitchHash::<P, B>::LARGEST_VIABLE_HASH_BITS
             {
@@ -186,6 +185,22 @@ mod test_compose_scompose_hash {
                 );
 
                 assert_eq!(recomposed_hash, encoded_hash);
+
+                let restored_hash = SwitchHash::<P, B>::restore(encoded_hash, hash_bits);
+                let (restored_index, restored_register, _) = HyperLogLog::<
+                    P,
+                    B,
+                    <P as PackedRegister<B>>::Array,
+                >::index_and_register_from_hash(
+                    restored_hash
+                );
+
+                assert_eq!(restored_index, index);
+                assert_eq!(restored_register, register);
+                assert_eq!(
+                    SwitchHash::<P, B>::encode(index, register, restored_hash, hash_bits),
+                    encoded_hash
+                );
             }
         }
     }
@@ -600,6 +615,97 @@ impl<P: Precision, B: Bits> SwitchHash<P, B> {
 
         (fragmented.register as u8, fragmented.index as usize)
     }
+
+    #[must_use]
+    #[inline]
+    /// Returns a hash with the provided index and register value.
+    ///
+    /// # Implementative details
+    /// All the bits that are not determined by the index and the register value are set to zero.
+    /// When the register value reaches the bit censored by the precision, we cannot know
+    /// the value of the censored bit, which we set to zero so that, at lower precisions,
+    /// the leading zeros continue into the index bits.
+    pub(super) fn restore_from_index_and_register(index: usize, register: u8) -> u64 {
+        debug_assert!(register > 0);
+        let mut restored_hash = u64::try_from(index).unwrap();
+        if u32::from(register) < 64 - u32::from(P::EXPONENT) {
+            restored_hash |= 1 << (64 - u32::from(register));
+        }
+        restored_hash
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns a hash that, once encoded with the provided hash bits, results in the provided hash.
+    ///
+    /// # Implementative details
+    /// The bits of the original hash that are not stored in the encoded hash are set to zero.
+    pub(super) fn restore(hash: u32, hash_bits: u8) -> u64 {
+        let fragment = Self::scompose_hash(hash, hash_bits);
+        let mut restored_hash = Self::restore_from_index_and_register(
+            usize::try_from(fragment.index).unwrap(),
+            fragment.register,
+        );
+
+        if P::EXPONENT + B::NUMBER_OF_BITS == hash_bits {
+            return restored_hash;
+        }
+
+        if fragment.register_flag() {
+            // The remainder contains the bits of the original hash that follow the
+            // leading bits censored by the register value.
+            restored_hash |= u64::from(fragment.hash_remainder)
+                << (64 - hash_bits + P::EXPONENT + B::NUMBER_OF_BITS);
+        } else {
+            // The remainder contains the negated leading bits of the original hash.
+            let shift = P::EXPONENT + 1 + 64 - hash_bits;
+            restored_hash |= fragment.restored_hash(hash_bits) & !((1 << shift) - 1);
+        }
+
+        restored_hash
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the register value of the provided hash, if it is exactly the hash that
+    /// [`Self::compose_hash`] returns for its index, register value and hash remainder.
+    ///
+    /// # Implementative details
+    /// This is meant to validate hashes read from untrusted sources, and as such it checks
+    /// that the register value is positive and reachable before decomposing the hash.
+    pub(super) fn canonical_register(hash: u32, hash_bits: u8) -> Option<u8> {
+        if u64::from(hash) >> hash_bits != 0 {
+            return None;
+        }
+
+        let register = if P::EXPONENT + B::NUMBER_OF_BITS == hash_bits {
+            u8::try_from(u64::from(hash) & B::MASK).ok()?
+        } else if flag::<P>(hash, hash_bits) {
+            let shift = hash_bits - 1 - P::EXPONENT - B::NUMBER_OF_BITS;
+            u8::try_from(u64::from(hash >> shift) & B::MASK).ok()?
+        } else {
+            // Without the flag, the register value is derived from the leading
+            // zeros of the original hash, and as such it is always positive.
+            1
+        };
+        if register == 0 {
+            return None;
+        }
+
+        let fragment = Self::scompose_hash(hash, hash_bits);
+        if fragment.register > maximal_register_value::<P, B>()
+            || Self::compose_hash(
+                fragment.index,
+                fragment.register,
+                fragment.hash_remainder,
+                hash_bits,
+            ) != hash
+        {
+            return None;
+        }
+
+        Some(fragment.register)
+    }
 }
 
 /// Iterator variants.
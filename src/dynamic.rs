@@ -0,0 +1,665 @@
+//! Submodule providing a [`HyperLogLog`] counter whose precision and bits are selected at runtime.
+use crate::prelude::*;
+use core::hash::{BuildHasherDefault, Hash};
+
+/// Implements the dynamic counter for the provided combinations of precision and bits.
+macro_rules! impl_dynamic_hyperloglog {
+    ($(($exponent: literal, $bits: literal)),*) => {
+        paste::paste! {
+            #[derive(Debug, Clone, PartialEq)]
+            /// A [`HyperLogLog`] counter whose precision and number of bits per register are selected at runtime.
+            ///
+            /// # Implementative details
+            /// Each combination of precision and bits is a variant wrapping the corresponding
+            /// [`HyperLogLog`] counter with [`PackedRegister::Vec`] registers. Operations involving
+            /// two counters return an error when the counters have different configurations.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            /// # use hyperloglog_rs::prelude::*;
+            ///
+            /// let mut hll1: DynamicHyperLogLog = DynamicHyperLogLog::new(8, 6).unwrap();
+            /// let mut hll2: DynamicHyperLogLog = DynamicHyperLogLog::new(8, 6).unwrap();
+            ///
+            /// hll1.insert(&42);
+            /// hll2.insert(&43);
+            ///
+            /// assert_eq!(hll1.precision(), 8);
+            /// assert_eq!(hll1.bits(), 6);
+            /// assert_eq!(hll1.estimate_union_cardinality(&hll2).unwrap().round(), 2.0);
+            ///
+            /// let hll3: DynamicHyperLogLog = DynamicHyperLogLog::new(10, 6).unwrap();
+            /// assert!(hll1.estimate_union_cardinality(&hll3).is_err());
+            ///
+            /// let invalid: Result<DynamicHyperLogLog, _> = DynamicHyperLogLog::new(19, 6);
+            /// assert!(invalid.is_err());
+            /// ```
+            pub enum DynamicHyperLogLog<H: HasherType = BuildHasherDefault<twox_hash::XxHash64>> {
+                $(
+                    #[doc = concat!("Counter with precision ", $exponent, " and ", $bits, " bits per register.")]
+                    [<Precision $exponent Bits $bits>](
+                        HyperLogLog<
+                            [<Precision $exponent>],
+                            [<Bits $bits>],
+                            <[<Precision $exponent>] as PackedRegister<[<Bits $bits>]>>::Vec,
+                            H,
+                        >,
+                    ),
+                )*
+            }
+
+            $(
+                impl<H: HasherType>
+                    From<
+                        HyperLogLog<
+                            [<Precision $exponent>],
+                            [<Bits $bits>],
+                            <[<Precision $exponent>] as PackedRegister<[<Bits $bits>]>>::Vec,
+                            H,
+                        >,
+                    > for DynamicHyperLogLog<H>
+                {
+                    #[inline]
+                    fn from(
+                        hll: HyperLogLog<
+                            [<Precision $exponent>],
+                            [<Bits $bits>],
+                            <[<Precision $exponent>] as PackedRegister<[<Bits $bits>]>>::Vec,
+                            H,
+                        >,
+                    ) -> Self {
+                        Self::[<Precision $exponent Bits $bits>](hll)
+                    }
+                }
+            )*
+
+            impl<H: HasherType + Default> DynamicHyperLogLog<H> {
+                #[inline]
+                /// Returns a new empty counter with the provided precision and number of bits per register.
+                ///
+                /// # Arguments
+                /// * `precision` - The exponent of the number of registers, between 4 and 18.
+                /// * `bits` - The number of bits per register, between 4 and 6.
+                ///
+                /// # Errors
+                /// If the provided combination of precision and bits is not supported.
+                pub fn new(precision: u8, bits: u8) -> Result<Self, Error> {
+                    Self::with_hasher(precision, bits, H::default())
+                }
+            }
+
+            impl<H: HasherType> DynamicHyperLogLog<H> {
+                #[inline]
+                /// Returns a new empty counter with the provided precision, number of bits per register and hasher builder.
+                ///
+                /// # Arguments
+                /// * `precision` - The exponent of the number of registers, between 4 and 18.
+                /// * `bits` - The number of bits per register, between 4 and 6.
+                /// * `build_hasher` - The builder of the hashers used to hash the elements.
+                ///
+                /// # Errors
+                /// If the provided combination of precision and bits is not supported.
+                pub fn with_hasher(
+                    precision: u8,
+                    bits: u8,
+                    build_hasher: H,
+                ) -> Result<Self, Error> {
+                    match (precision, bits) {
+                        $(
+                            ($exponent, $bits) => Ok(Self::[<Precision $exponent Bits $bits>](
+                                HyperLogLog::with_hasher(build_hasher),
+                            )),
+                        )*
+                        (0..=3 | 19.., _) => Err(Error::InvalidArgument("The precision must be between 4 and 18.")),
+                        _ => Err(Error::InvalidArgument("The number of bits must be between 4 and 6.")),
+                    }
+                }
+
+                #[inline]
+                /// Returns the exponent of the number of registers of the counter.
+                pub fn precision(&self) -> u8 {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](_) => $exponent,)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the number of bits per register of the counter.
+                pub fn bits(&self) -> u8 {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](_) => $bits,)*
+                    }
+                }
+
+                #[inline]
+                /// Returns a reference to the builder of the hashers used to hash the elements.
+                pub fn hasher(&self) -> &H {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.hasher(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the counter is empty.
+                pub fn is_empty(&self) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.is_empty(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the counter is fully saturated.
+                pub fn is_full(&self) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.is_full(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the counter is in hash list mode.
+                pub fn is_hash_list(&self) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.is_hash_list(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the number of registers equal to zero.
+                ///
+                /// # Errors
+                /// If the counter is in hash list mode.
+                pub fn number_of_zero_registers(&self) -> Result<usize, Error> {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.number_of_zero_registers(),)*
+                    }
+                }
+
+                #[inline]
+                /// Inserts an element into the counter.
+                pub fn insert<T: Hash>(&mut self, element: &T) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.insert(element),)*
+                    }
+                }
+
+                #[inline]
+                /// Inserts the element with the provided hash into the counter.
+                pub fn insert_hash(&mut self, hash: u64) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.insert_hash(hash),)*
+                    }
+                }
+
+                #[inline]
+                /// Inserts the provided elements into the counter.
+                pub fn insert_many<T: Hash>(&mut self, elements: &[T]) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.insert_many(elements),)*
+                    }
+                }
+
+                #[inline]
+                /// Inserts the elements with the provided hashes into the counter.
+                pub fn insert_many_hashes(&mut self, hashes: &[u64]) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.insert_many_hashes(hashes),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the provided element may be contained in the counter.
+                pub fn may_contain<T: Hash>(&self, element: &T) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.may_contain(element),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the element with the provided hash may be contained in the counter.
+                pub fn may_contain_hash(&self, hash: u64) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.may_contain_hash(hash),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns whether all of the elements with the provided hashes may be contained in the counter.
+                pub fn may_contain_all_hashes(&self, hashes: &[u64]) -> bool {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.may_contain_all_hashes(hashes),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the uncorrected estimate of the cardinality.
+                pub fn uncorrected_estimate_cardinality(&self) -> f64 {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.uncorrected_estimate_cardinality(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the corrected estimate of the cardinality.
+                pub fn estimate_cardinality(&self) -> f64 {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.estimate_cardinality(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the improved raw estimate of the cardinality, which requires no empirical correction.
+                pub fn improved_estimate_cardinality(&self) -> f64 {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.improved_estimate_cardinality(),)*
+                    }
+                }
+
+                #[inline]
+                /// Returns the estimate of the cardinality, with the interval expected to contain
+                /// the exact cardinality with the provided confidence.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_cardinality_with_confidence(&self, confidence: f64) -> Estimate {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.estimate_cardinality_with_confidence(confidence),)*
+                    }
+                }
+
+                #[inline]
+                /// Merges the provided counter into the current one.
+                ///
+                /// # Errors
+                /// * If the two counters have different precisions or bits.
+                /// * If the merge of the two counters fails, as reported by [`HyperLogLog::try_merge`].
+                pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => hll.try_merge(other),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns whether the provided counter may be fully contained in the current counter.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn may_contain_all(&self, other: &Self) -> Result<bool, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.may_contain_all(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the union cardinality between two counters.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn estimate_union_cardinality(&self, other: &Self) -> Result<f64, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_union_cardinality(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns the improved raw estimate of the union cardinality between two counters.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn improved_estimate_union_cardinality(&self, other: &Self) -> Result<f64, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.improved_estimate_union_cardinality(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the union cardinality between two counters, with the interval
+                /// expected to contain the exact union cardinality with the provided confidence.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_union_cardinality_with_confidence(&self, other: &Self, confidence: f64) -> Result<Estimate, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_union_cardinality_with_confidence(other, confidence)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the intersection cardinality between two counters.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn estimate_intersection_cardinality(&self, other: &Self) -> Result<f64, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_intersection_cardinality(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the intersection cardinality between two counters, with the interval
+                /// expected to contain the exact intersection cardinality with the provided confidence.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_intersection_cardinality_with_confidence(&self, other: &Self, confidence: f64) -> Result<Estimate, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_intersection_cardinality_with_confidence(other, confidence)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the Jaccard index between two counters.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn estimate_jaccard_index(&self, other: &Self) -> Result<f64, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_jaccard_index(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the Jaccard index between two counters, with the interval
+                /// expected to contain the exact Jaccard index with the provided confidence.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_jaccard_index_with_confidence(&self, other: &Self, confidence: f64) -> Result<Estimate, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_jaccard_index_with_confidence(other, confidence)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the difference cardinality between two counters.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                pub fn estimate_difference_cardinality(&self, other: &Self) -> Result<f64, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_difference_cardinality(other)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the difference cardinality between two counters, with the interval
+                /// expected to contain the exact difference cardinality with the provided confidence.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_difference_cardinality_with_confidence(&self, other: &Self, confidence: f64) -> Result<Estimate, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_difference_cardinality_with_confidence(other, confidence)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+
+                #[inline]
+                /// Returns an estimate of the fraction of the elements of the current counter that are also
+                /// in the other counter, with the interval expected to contain the exact fraction with the
+                /// provided confidence.
+                ///
+                /// # Errors
+                /// If the two counters have different precisions or bits.
+                ///
+                /// # Panics
+                /// * If the confidence is not strictly between zero and one.
+                pub fn estimate_containment(&self, other: &Self, confidence: f64) -> Result<Estimate, Error> {
+                    match (self, other) {
+                        $(
+                            (
+                                Self::[<Precision $exponent Bits $bits>](hll),
+                                Self::[<Precision $exponent Bits $bits>](other),
+                            ) => Ok(hll.estimate_containment(other, confidence)),
+                        )*
+                        _ => Err(INCOMPATIBLE_COUNTERS),
+                    }
+                }
+            }
+
+            impl<T: Hash, H: HasherType> Extend<T> for DynamicHyperLogLog<H> {
+                #[inline]
+                fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                    match self {
+                        $(Self::[<Precision $exponent Bits $bits>](hll) => hll.extend(iter),)*
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// Error returned when combining counters with different precisions or bits.
+const INCOMPATIBLE_COUNTERS: Error =
+    Error::IncompatibleConfiguration("The counters have different precisions or bits.");
+
+impl_dynamic_hyperloglog!(
+    (4, 4),
+    (4, 5),
+    (4, 6),
+    (5, 4),
+    (5, 5),
+    (5, 6),
+    (6, 4),
+    (6, 5),
+    (6, 6),
+    (7, 4),
+    (7, 5),
+    (7, 6),
+    (8, 4),
+    (8, 5),
+    (8, 6),
+    (9, 4),
+    (9, 5),
+    (9, 6),
+    (10, 4),
+    (10, 5),
+    (10, 6),
+    (11, 4),
+    (11, 5),
+    (11, 6),
+    (12, 4),
+    (12, 5),
+    (12, 6),
+    (13, 4),
+    (13, 5),
+    (13, 6),
+    (14, 4),
+    (14, 5),
+    (14, 6),
+    (15, 4),
+    (15, 5),
+    (15, 6),
+    (16, 4),
+    (16, 5),
+    (16, 6),
+    (17, 4),
+    (17, 5),
+    (17, 6),
+    (18, 4),
+    (18, 5),
+    (18, 6)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    #[test]
+    fn test_dynamic_configurations() {
+        for precision in 0..=20 {
+            for bits in 0..=8 {
+                let hll: Result<DynamicHyperLogLog, _> = DynamicHyperLogLog::new(precision, bits);
+                if (4..=18).contains(&precision) && (4..=6).contains(&bits) {
+                    let hll = hll.unwrap();
+                    assert_eq!(hll.precision(), precision);
+                    assert_eq!(hll.bits(), bits);
+                    assert!(hll.is_empty());
+                } else {
+                    assert!(hll.is_err());
+                }
+            }
+        }
+
+        let mut left: DynamicHyperLogLog = DynamicHyperLogLog::new(8, 6).unwrap();
+        let other_precision: DynamicHyperLogLog = DynamicHyperLogLog::new(9, 6).unwrap();
+        let other_bits: DynamicHyperLogLog = DynamicHyperLogLog::new(8, 5).unwrap();
+
+        for other in [&other_precision, &other_bits] {
+            assert!(left.clone().merge(other).is_err());
+            assert!(left.may_contain_all(other).is_err());
+            assert!(left.estimate_union_cardinality(other).is_err());
+            assert!(left.estimate_intersection_cardinality(other).is_err());
+            assert!(left.estimate_jaccard_index(other).is_err());
+            assert!(left.estimate_difference_cardinality(other).is_err());
+            assert!(left
+                .estimate_intersection_cardinality_with_confidence(other, 0.95)
+                .is_err());
+            assert!(left.estimate_containment(other, 0.95).is_err());
+        }
+
+        let right = left.clone();
+        assert!(left.merge(&right).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dynamic_merge_with_different_hasher_builders() {
+        use std::collections::hash_map::RandomState;
+
+        let mut left = DynamicHyperLogLog::with_hasher(8, 6, RandomState::new()).unwrap();
+        let right = DynamicHyperLogLog::with_hasher(8, 6, RandomState::new()).unwrap();
+
+        assert!(matches!(
+            left.merge(&right),
+            Err(Error::IncompatibleConfiguration(_))
+        ));
+        assert!(left.merge(&left.clone()).is_ok());
+    }
+
+    #[test_precisions_and_bits]
+    fn test_dynamic_matches_static<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+        DynamicHyperLogLog: From<HyperLogLog<P, B, <P as PackedRegister<B>>::Vec>>,
+    {
+        let mut dynamic: DynamicHyperLogLog =
+            DynamicHyperLogLog::new(P::EXPONENT, B::NUMBER_OF_BITS).unwrap();
+        let mut reference = HyperLogLog::<P, B, <P as PackedRegister<B>>::Vec>::default();
+
+        for position in 0..5_000_u64 {
+            let element = splitmix64(position);
+            assert_eq!(dynamic.insert(&element), reference.insert(&element));
+            assert!(dynamic.may_contain(&element));
+        }
+
+        assert_eq!(dynamic.is_hash_list(), reference.is_hash_list());
+        assert_eq!(
+            dynamic.uncorrected_estimate_cardinality().to_bits(),
+            reference.uncorrected_estimate_cardinality().to_bits()
+        );
+        assert_eq!(
+            dynamic.improved_estimate_cardinality().to_bits(),
+            reference.improved_estimate_cardinality().to_bits()
+        );
+        assert_eq!(
+            dynamic.estimate_cardinality().to_bits(),
+            reference.estimate_cardinality().to_bits()
+        );
+        assert_eq!(
+            dynamic
+                .estimate_union_cardinality(&dynamic)
+                .unwrap()
+                .to_bits(),
+            reference.estimate_union_cardinality(&reference).to_bits()
+        );
+        assert_eq!(
+            dynamic
+                .estimate_intersection_cardinality_with_confidence(&dynamic, 0.95)
+                .unwrap(),
+            reference.estimate_intersection_cardinality_with_confidence(&reference, 0.95)
+        );
+
+        let converted = DynamicHyperLogLog::from(reference);
+        assert_eq!(converted.precision(), P::EXPONENT);
+        assert_eq!(converted.bits(), B::NUMBER_OF_BITS);
+    }
+}
@@ -0,0 +1,94 @@
+//! Submodule providing the [`Error`] returned by the fallible operations of the crate.
+use core::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The representation a counter employs to store the observed elements.
+pub enum Mode {
+    /// The counter stores a sorted list of the hashes of the elements.
+    HashList,
+    /// The counter stores the registers of the elements.
+    Registers,
+}
+
+impl Display for Mode {
+    #[inline]
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str(match self {
+            Mode::HashList => "hash list",
+            Mode::Registers => "registers",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+/// Errors returned by the fallible operations of the counters and of the sketches.
+pub enum Error {
+    /// The counter is not in the mode required by the operation.
+    ModeMismatch {
+        /// The mode required by the operation.
+        expected: Mode,
+    },
+    /// The storage of the counter is full and cannot be extended any further.
+    CapacityExhausted,
+    /// The configurations of the counters, such as their precision, bits or hasher, are incompatible.
+    IncompatibleConfiguration(&'static str),
+    /// The provided data does not describe a valid counter.
+    CorruptData(&'static str),
+    /// The provided arguments are not valid.
+    InvalidArgument(&'static str),
+}
+
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::ModeMismatch { expected } => {
+                write!(formatter, "The counter is not in {expected} mode.")
+            }
+            Error::CapacityExhausted => {
+                formatter.write_str("The storage of the counter cannot be extended any further.")
+            }
+            Error::IncompatibleConfiguration(message)
+            | Error::CorruptData(message)
+            | Error::InvalidArgument(message) => formatter.write_str(message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::ModeMismatch {
+                expected: Mode::HashList
+            }
+            .to_string(),
+            "The counter is not in hash list mode."
+        );
+        assert_eq!(
+            Error::ModeMismatch {
+                expected: Mode::Registers
+            }
+            .to_string(),
+            "The counter is not in registers mode."
+        );
+        assert_eq!(
+            Error::CorruptData("The checksum does not match.").to_string(),
+            "The checksum does not match."
+        );
+
+        let error: Box<dyn std::error::Error> = Box::new(Error::CapacityExhausted);
+        assert_eq!(
+            error.to_string(),
+            "The storage of the counter cannot be extended any further."
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Submodule providing the [`Estimate`] struct, an estimate paired with its confidence interval.
+use crate::utils::FloatOps;
+
+/// Number of terms of the Maclaurin series employed to evaluate the error function.
+const ERROR_FUNCTION_TERMS: u32 = 100;
+/// Number of bisection steps employed to invert the error function.
+const QUANTILE_BISECTION_STEPS: u32 = 64;
+/// Largest quantile of the standard normal distribution considered, beyond which the
+/// series evaluating the error function loses precision.
+const MAXIMAL_QUANTILE: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An estimate paired with the interval expected to contain the exact value.
+pub struct Estimate {
+    /// The point estimate.
+    pub value: f64,
+    /// The lower bound of the interval.
+    pub lower: f64,
+    /// The upper bound of the interval.
+    pub upper: f64,
+    /// The probability that the interval contains the exact value.
+    pub confidence: f64,
+}
+
+impl Estimate {
+    #[inline]
+    #[must_use]
+    /// Returns an estimate with the provided bounds, widened to contain the point estimate.
+    pub(crate) fn new(value: f64, lower: f64, upper: f64, confidence: f64) -> Self {
+        Self {
+            value,
+            lower: lower.min(value),
+            upper: upper.max(value),
+            confidence,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns an estimate whose interval spans the provided relative standard error.
+    ///
+    /// # Arguments
+    /// * `value` - The point estimate.
+    /// * `relative_error` - The standard error of the estimate relative to its value.
+    /// * `confidence` - The probability that the interval contains the exact value.
+    pub(crate) fn from_relative_error(value: f64, relative_error: f64, confidence: f64) -> Self {
+        Self::from_standard_error(value, value * relative_error, confidence)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns a non-negative estimate whose interval spans the provided standard error.
+    ///
+    /// # Arguments
+    /// * `value` - The point estimate.
+    /// * `standard_error` - The standard error of the estimate.
+    /// * `confidence` - The probability that the interval contains the exact value.
+    pub(crate) fn from_standard_error(value: f64, standard_error: f64, confidence: f64) -> Self {
+        let margin = standard_error * standard_normal_quantile(confidence);
+        Self::new(value, (value - margin).max(0.0), value + margin, confidence)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns an estimate from the number of distinct hashes observed with the provided hash bits.
+    ///
+    /// # Arguments
+    /// * `value` - The point estimate.
+    /// * `number_of_hashes` - The number of distinct hashes, each belonging to at least one element.
+    /// * `duplicates` - The number of hashes known to have collided with another hash.
+    /// * `hash_bits` - The number of bits of the hashes.
+    /// * `register_bits` - The number of bits of the register values encoded in the hashes.
+    /// * `relative_error` - The standard error of the registers relative to their estimate.
+    /// * `confidence` - The probability that the interval contains the exact value.
+    ///
+    /// # Implementative details
+    /// The hashes of distinct elements may collide unnoticed, which we model as a Poisson
+    /// process whose mean is the largest between the expected collisions and the excess of
+    /// the point estimate over the known hashes. The hashes are composed of an index, the
+    /// geometrically distributed register value and uniformly distributed bits, so that two
+    /// of them collide with probability `2^register_bits / (3 * 2^hash_bits)`.
+    /// Known duplicates mean that the hashes were downgraded to fewer bits, which mostly
+    /// encode the register values and are therefore far from uniformly distributed: the
+    /// upper bound is then never lower than the one spanning the error of the registers.
+    pub(crate) fn from_hashes(
+        value: f64,
+        number_of_hashes: u32,
+        duplicates: u32,
+        hash_bits: u8,
+        register_bits: u8,
+        relative_error: f64,
+        confidence: f64,
+    ) -> Self {
+        let quantile = standard_normal_quantile(confidence);
+        let known = f64::from(number_of_hashes) + f64::from(duplicates);
+        let expected_collisions = (f64::from(number_of_hashes)
+            * f64::from(number_of_hashes)
+            * f64::integer_exp2(register_bits)
+            / (6.0 * f64::integer_exp2(hash_bits)))
+        .max(value - known);
+        let mut upper = known + expected_collisions + quantile * square_root(expected_collisions);
+        if duplicates > 0 {
+            upper = upper.max(value + quantile * value * relative_error);
+        }
+        Self::new(value, f64::from(number_of_hashes), upper, confidence)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the width of the interval.
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns whether the interval contains the provided value.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+}
+
+#[inline]
+#[must_use]
+/// Returns the confidence each of the provided number of estimates must have so that
+/// intervals combining all of them have at least the provided confidence.
+///
+/// # Implementative details
+/// By the union bound, the probability that any of the intervals misses its exact value
+/// is at most the sum of the probabilities that each of them does.
+pub(crate) fn split_confidence(confidence: f64, number_of_estimates: u8) -> f64 {
+    1.0 - (1.0 - confidence) / f64::from(number_of_estimates)
+}
+
+#[inline]
+/// Returns the square root of the provided non-negative value.
+pub(crate) fn square_root(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    // Newton's method converges quadratically from any starting point above the root.
+    let mut root = value.max(1.0);
+    loop {
+        let next = f64::midpoint(root, value / root);
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+#[inline]
+/// Returns the error function of the provided value.
+fn error_function(value: f64) -> f64 {
+    // erf(x) = 2 / sqrt(pi) * sum_n (-1)^n x^(2n + 1) / (n! (2n + 1))
+    let squared = value * value;
+    let mut term = value;
+    let mut sum = value;
+    for n in 1..ERROR_FUNCTION_TERMS {
+        term *= -squared / f64::from(n);
+        sum += term / f64::from(2 * n + 1);
+    }
+    core::f64::consts::FRAC_2_SQRT_PI * sum
+}
+
+#[inline]
+/// Returns the number of standard deviations from the mean of a normal distribution
+/// within which the provided fraction of its mass lies.
+fn standard_normal_quantile(confidence: f64) -> f64 {
+    // The mass of a standard normal distribution within z standard deviations
+    // of the mean is erf(z / sqrt(2)), which we invert by bisection.
+    let mut lower = 0.0;
+    let mut upper = MAXIMAL_QUANTILE;
+    for _ in 0..QUANTILE_BISECTION_STEPS {
+        let middle = f64::midpoint(lower, upper);
+        if error_function(middle * core::f64::consts::FRAC_1_SQRT_2) < confidence {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    upper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_normal_quantile() {
+        for (confidence, quantile) in [
+            (0.6827, 1.0),
+            (0.9, 1.644_853_6),
+            (0.95, 1.959_964),
+            (0.9545, 2.0),
+            (0.99, 2.575_829_3),
+            (0.9973, 3.0),
+        ] {
+            assert!(
+                (standard_normal_quantile(confidence) - quantile).abs() < 1e-3,
+                "The quantile of {confidence} should be {quantile}, got {}.",
+                standard_normal_quantile(confidence)
+            );
+        }
+    }
+
+    #[test]
+    fn test_estimate_bounds() {
+        let estimate = Estimate::from_relative_error(100.0, 0.1, 0.95);
+        assert!(estimate.contains(100.0));
+        assert!((estimate.lower - 80.4).abs() < 0.01);
+        assert!((estimate.upper - 119.6).abs() < 0.01);
+        assert!((estimate.width() - 39.2).abs() < 0.01);
+
+        let estimate = Estimate::new(10.0, 12.0, 15.0, 0.95);
+        assert!((estimate.lower - 10.0).abs() < f64::EPSILON);
+        assert!((estimate.upper - 15.0).abs() < f64::EPSILON);
+
+        assert!((split_confidence(0.94, 3) - 0.98).abs() < 1e-12);
+
+        let estimate = Estimate::from_hashes(100.0, 100, 0, 32, 6, 0.1, 0.99);
+        assert!((estimate.lower - 100.0).abs() < f64::EPSILON);
+        assert!(estimate.upper < 101.0);
+
+        let estimate = Estimate::from_hashes(110.0, 100, 5, 16, 6, 0.1, 0.99);
+        assert!((estimate.lower - 100.0).abs() < f64::EPSILON);
+        assert!(estimate.upper > 110.0);
+        assert!(estimate.upper >= 110.0 * (1.0 + 0.1 * standard_normal_quantile(0.99)));
+    }
+
+    #[test]
+    fn test_square_root() {
+        for value in [0.0, 0.25, 1.0, 2.0, 9.0, 1e6, 12_345.678] {
+            let root = square_root(value);
+            assert!((root * root - value).abs() <= 1e-9 * value.max(1.0));
+        }
+    }
+}
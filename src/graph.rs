@@ -0,0 +1,330 @@
+//! Submodule providing the propagation of [`HyperLogLog`] counters over graphs, computing
+//! the neighbourhoods of the nodes within increasing distances as done by [`HyperBall`].
+use crate::prelude::*;
+use core::hash::BuildHasherDefault;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A directed graph in compressed sparse row format.
+///
+/// # Implementative details
+/// The destinations of the edges leaving the node `i` are stored in the slice of the
+/// destinations between the offsets `i` and `i + 1`, hence there is one more offset than nodes.
+pub struct CsrGraph<'a> {
+    /// The offsets of the destinations of each node.
+    offsets: &'a [usize],
+    /// The destinations of the edges, sorted by source node.
+    destinations: &'a [usize],
+}
+
+impl<'a> CsrGraph<'a> {
+    /// Returns a new graph with the provided offsets and destinations.
+    ///
+    /// # Arguments
+    /// * `offsets` - The offsets of the destinations of each node, followed by the number of edges.
+    /// * `destinations` - The destinations of the edges, sorted by source node.
+    ///
+    /// # Errors
+    /// * If the offsets are empty, do not start from zero or are not sorted.
+    /// * If the last offset is not the number of destinations.
+    /// * If a destination is not a node of the graph.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// // The path 0 -> 1 -> 2.
+    /// let graph = CsrGraph::new(&[0, 1, 2, 2], &[1, 2]).unwrap();
+    ///
+    /// assert_eq!(graph.number_of_nodes(), 3);
+    /// assert_eq!(graph.number_of_edges(), 2);
+    /// assert_eq!(graph.neighbours(0), &[1]);
+    /// assert!(graph.neighbours(2).is_empty());
+    ///
+    /// assert!(CsrGraph::new(&[0, 1, 2], &[1, 2]).is_err());
+    /// ```
+    pub fn new(offsets: &'a [usize], destinations: &'a [usize]) -> Result<Self, Error> {
+        if offsets.first() != Some(&0) {
+            return Err(Error::InvalidArgument("The offsets must start from zero."));
+        }
+        if offsets.windows(2).any(|window| window[0] > window[1]) {
+            return Err(Error::InvalidArgument("The offsets must be sorted."));
+        }
+        if offsets.last() != Some(&destinations.len()) {
+            return Err(Error::InvalidArgument(
+                "The last offset must be the number of destinations.",
+            ));
+        }
+        let number_of_nodes = offsets.len() - 1;
+        if destinations
+            .iter()
+            .any(|&destination| destination >= number_of_nodes)
+        {
+            return Err(Error::InvalidArgument(
+                "The destinations must be nodes of the graph.",
+            ));
+        }
+        Ok(Self {
+            offsets,
+            destinations,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of nodes of the graph.
+    pub fn number_of_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of edges of the graph.
+    pub fn number_of_edges(&self) -> usize {
+        self.destinations.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the destinations of the edges leaving the provided node.
+    ///
+    /// # Panics
+    /// If the node is not a node of the graph.
+    pub fn neighbours(&self, node: usize) -> &'a [usize] {
+        &self.destinations[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The [`HyperLogLog`] counters of the nodes reachable from each node of a graph within increasing distances.
+///
+/// # Implementative details
+/// The counter of a node at distance one holds the node and its neighbours, while the counter
+/// at distance `k` is the union of the counters of the node and of its neighbours at distance
+/// `k - 1`, as in [`HyperBall`]. The counters are stored node after node, with the counters of each
+/// node sorted by increasing distance, which is the layout expected by
+/// [`HyperSpheresSketch::edge_features`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// // The path 0 -> 1 -> 2 -> 3.
+/// let graph = CsrGraph::new(&[0, 1, 2, 3, 3], &[1, 2, 3]).unwrap();
+/// let hyperball: HyperBall<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+///     HyperBall::new(&graph, 2);
+///
+/// let balls = hyperball.node_sketches(0);
+/// assert_eq!(balls.len(), 2);
+/// assert_eq!(balls[0].estimate_cardinality().round(), 2.0);
+/// assert_eq!(balls[1].estimate_cardinality().round(), 3.0);
+///
+/// let neighbourhood_function = hyperball.neighbourhood_function();
+/// assert_eq!(neighbourhood_function.len(), 3);
+/// assert_eq!(neighbourhood_function[0], 4.0);
+/// assert_eq!(neighbourhood_function[2].round(), 9.0);
+/// ```
+pub struct HyperBall<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    Hasher: HasherType = BuildHasherDefault<twox_hash::XxHash64>,
+> {
+    /// The counters of each node, sorted by increasing distance.
+    sketches: Vec<HyperLogLog<P, B, R, Hasher>>,
+    /// The number of nodes of the graph.
+    number_of_nodes: usize,
+    /// The number of distances of the counters of each node.
+    number_of_hops: usize,
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> HyperBall<P, B, R, H> {
+    #[must_use]
+    /// Computes the counters of the nodes reachable from each node of the graph within distances
+    /// from one to the provided number of hops.
+    ///
+    /// # Arguments
+    /// * `graph` - The graph whose edges are followed from source to destination.
+    /// * `number_of_hops` - The largest distance of the counters of each node.
+    pub fn new(graph: &CsrGraph<'_>, number_of_hops: usize) -> Self {
+        let number_of_nodes = graph.number_of_nodes();
+        let mut sketches: Vec<HyperLogLog<P, B, R, H>> =
+            Vec::with_capacity(number_of_nodes * number_of_hops);
+
+        for node in 0..number_of_nodes {
+            if number_of_hops == 0 {
+                break;
+            }
+            let mut ball = HyperLogLog::default();
+            ball.insert(&node);
+            ball.extend(graph.neighbours(node));
+            sketches.push(ball);
+            sketches.extend((1..number_of_hops).map(|_| HyperLogLog::default()));
+        }
+
+        for hop in 1..number_of_hops {
+            for node in 0..number_of_nodes {
+                let mut ball = sketches[node * number_of_hops + hop - 1].clone();
+                for &neighbour in graph.neighbours(node) {
+                    ball |= &sketches[neighbour * number_of_hops + hop - 1];
+                }
+                sketches[node * number_of_hops + hop] = ball;
+            }
+        }
+
+        Self {
+            sketches,
+            number_of_nodes,
+            number_of_hops,
+        }
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperBall<P, B, R, H> {
+    #[inline]
+    #[must_use]
+    /// Returns the number of nodes of the graph.
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of counters of each node.
+    pub fn number_of_hops(&self) -> usize {
+        self.number_of_hops
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the counters of all of the nodes, stored node after node.
+    pub fn sketches(&self) -> &[HyperLogLog<P, B, R, H>] {
+        &self.sketches
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the counters of the provided node, sorted by increasing distance.
+    ///
+    /// # Panics
+    /// If the node is not a node of the graph.
+    pub fn node_sketches(&self, node: usize) -> &[HyperLogLog<P, B, R, H>] {
+        assert!(
+            node < self.number_of_nodes,
+            "The node {node} is not a node of the graph."
+        );
+        &self.sketches[node * self.number_of_hops..(node + 1) * self.number_of_hops]
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the counters of all of the nodes, stored node after node.
+    pub fn into_sketches(self) -> Vec<HyperLogLog<P, B, R, H>> {
+        self.sketches
+    }
+
+    #[must_use]
+    // The number of nodes is exactly representable as long as it is below 2^53.
+    #[allow(clippy::cast_precision_loss)]
+    /// Returns the approximated neighbourhood function of the graph.
+    ///
+    /// # Implementative details
+    /// The value at position `k` is the estimated number of pairs of nodes whose distance
+    /// is at most `k`, hence the first value is the number of nodes of the graph.
+    pub fn neighbourhood_function(&self) -> Vec<f64> {
+        let mut neighbourhood_function = vec![0.0; self.number_of_hops + 1];
+        neighbourhood_function[0] = self.number_of_nodes as f64;
+        for node_sketches in self.sketches.chunks_exact(self.number_of_hops.max(1)) {
+            for (value, sketch) in neighbourhood_function[1..].iter_mut().zip(node_sketches) {
+                *value += sketch.estimate_cardinality();
+            }
+        }
+        neighbourhood_function
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+    use std::collections::HashSet;
+
+    /// Returns the nodes reachable from the provided node within the provided distance.
+    fn exact_ball(graph: &CsrGraph<'_>, node: usize, distance: usize) -> HashSet<usize> {
+        let mut ball = HashSet::from([node]);
+        let mut frontier = vec![node];
+        for _ in 0..distance {
+            frontier = frontier
+                .iter()
+                .flat_map(|&node| graph.neighbours(node).iter().copied())
+                .filter(|&neighbour| ball.insert(neighbour))
+                .collect();
+        }
+        ball
+    }
+
+    #[test_precisions_and_bits]
+    fn test_hyperball<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let number_of_nodes = 30;
+        let number_of_hops = 3;
+        let mut random_state = splitmix64(u64::from(P::EXPONENT));
+        let mut offsets = vec![0];
+        let mut destinations = Vec::new();
+        for _ in 0..number_of_nodes {
+            for _ in 0..2 {
+                random_state = splitmix64(random_state);
+                destinations.push(usize::try_from(random_state >> 32).unwrap() % number_of_nodes);
+            }
+            offsets.push(destinations.len());
+        }
+        let graph = CsrGraph::new(&offsets, &destinations).unwrap();
+
+        let hyperball: HyperBall<P, B, <P as PackedRegister<B>>::Array> =
+            HyperBall::new(&graph, number_of_hops);
+        assert_eq!(hyperball.number_of_nodes(), number_of_nodes);
+        assert_eq!(hyperball.number_of_hops(), number_of_hops);
+        assert_eq!(hyperball.sketches().len(), number_of_nodes * number_of_hops);
+
+        let exact_number_of_nodes = f64::from(u32::try_from(number_of_nodes).unwrap());
+        let mut exact_neighbourhood_function = vec![0.0; number_of_hops + 1];
+        exact_neighbourhood_function[0] = exact_number_of_nodes;
+        for node in 0..number_of_nodes {
+            for (hop, sketch) in hyperball.node_sketches(node).iter().enumerate() {
+                let ball = exact_ball(&graph, node, hop + 1);
+                exact_neighbourhood_function[hop + 1] +=
+                    f64::from(u32::try_from(ball.len()).unwrap());
+
+                // Merging the counters of the neighbours is equivalent to inserting the ball.
+                let inserted: HyperLogLog<P, B, <P as PackedRegister<B>>::Array> =
+                    ball.iter().collect();
+                assert!(sketch.may_contain_all(&inserted));
+                assert!(inserted.may_contain_all(sketch));
+            }
+        }
+
+        let neighbourhood_function = hyperball.neighbourhood_function();
+        assert_eq!(
+            neighbourhood_function[0].to_bits(),
+            exact_neighbourhood_function[0].to_bits()
+        );
+        for (estimate, exact) in neighbourhood_function
+            .iter()
+            .zip(exact_neighbourhood_function.iter())
+        {
+            assert!(
+                (estimate - exact).abs() <= exact * 3.0 * P::error_rate(),
+                "The estimated neighbourhood function ({estimate}) is too far from the exact one ({exact})."
+            );
+        }
+
+        let empty: HyperBall<P, B, <P as PackedRegister<B>>::Array> = HyperBall::new(&graph, 0);
+        assert!(empty.sketches().is_empty());
+        assert!(empty.node_sketches(0).is_empty());
+        assert_eq!(empty.neighbourhood_function(), [exact_number_of_nodes]);
+    }
+}
@@ -3,24 +3,22 @@ mod float_bits_ops;
 use crate::{
     composite_hash::GapHash,
     prelude::{Bits, HasherType, HyperLogLog, Precision, Registers},
-    utils::{correct_union_estimate, FloatOps},
+    utils::FloatOps,
 };
+pub(crate) use float_bits_ops::{MAXIMAL_DUPLICATES, MAXIMAL_NUMBER_OF_HASHES};
 
 impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B, R, H> {
     #[inline]
-    /// Returns the union estimation from a decreasingly sorted iterator and a counter.
+    /// Returns the harmonic sum of the union from a decreasingly sorted iterator and a counter.
     ///
     /// # Implementative details
     /// The provided iterator is expected to be sorted in ascending order,
     /// in such a way that hash values that point to the same index are contiguos,
     /// and ordered by value of the register as well.
-    pub(crate) fn union_estimation_from_sorted_iterator_and_counter(
-        &self,
-        other: &Self,
-        left_cardinality: f64,
-        right_cardinality: f64,
-    ) -> f64 {
-        let mut harmonic_sum = self.harmonic_sum;
+    pub(crate) fn union_harmonic_sum_from_sorted_iterator_and_counter(&self, other: &Self) -> f64 {
+        // The harmonic sum of the hash list holds its metadata, so we start from the
+        // harmonic sum of the registers of the other counter.
+        let mut harmonic_sum = other.harmonic_sum;
         // We set the previous index to the NUMBER OF REGISTERS, which is a value higher
         // than the maximal possible index, so that the first value is always considered
         // as a new value.
@@ -58,10 +56,55 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
                 - f64::integer_exp2_minus(right_register_value);
         }
 
-        correct_union_estimate(
-            left_cardinality,
-            right_cardinality,
-            P::ALPHA * f64::integer_exp2(P::EXPONENT + P::EXPONENT) / harmonic_sum,
-        )
+        harmonic_sum
+    }
+
+    #[inline]
+    /// Returns the number of hashes in the hash list that may be contained in the provided
+    /// counter, the number of hashes, and the number of hashes expected to be reported
+    /// as contained by chance.
+    ///
+    /// # Arguments
+    /// * `other` - The counter in registers mode to check the hashes against.
+    ///
+    /// # Implementative details
+    /// A hash is reported as contained when the register of the other counter at its index
+    /// is at least as large as its register value. For a hash not in the other counter, this
+    /// happens with the probability that a register of the other counter is at least as large,
+    /// which we approximate with the fraction of the registers that are.
+    pub(crate) fn containment_from_sorted_iterator_and_counter(
+        &self,
+        other: &Self,
+    ) -> (u32, u32, f64) {
+        debug_assert!(self.is_hash_list());
+        debug_assert!(!other.is_hash_list());
+
+        // We compute the fraction of registers of the other counter that are
+        // at least as large as each register value.
+        let histogram = other.register_histogram();
+        let mut at_least = [0.0_f64; 65];
+        for (register_value, count) in histogram.iter().enumerate().rev() {
+            at_least[register_value] = at_least[register_value + 1] + f64::from(*count);
+        }
+        for fraction in &mut at_least {
+            *fraction /= f64::integer_exp2(P::EXPONENT);
+        }
+
+        let mut contained = 0;
+        let mut number_of_hashes = 0;
+        let mut false_positives = 0.0;
+
+        for (register_value, index) in GapHash::<P, B>::decoded(
+            self.registers.as_ref(),
+            self.get_number_of_hashes().unwrap(),
+            self.get_hash_bits().unwrap(),
+            self.get_writer_tell(),
+        ) {
+            number_of_hashes += 1;
+            contained += u32::from(other.registers.get_register(index) >= register_value);
+            false_positives += at_least[usize::from(register_value)];
+        }
+
+        (contained, number_of_hashes, false_positives)
     }
 }
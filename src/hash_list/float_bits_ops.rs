@@ -11,14 +11,16 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
 
     #[inline]
     /// Returns the number of bits used to store the hash.
-    /// 
+    ///
     /// # Errors
     /// If the counter is not in hash list mode, an error is returned.
-    pub fn get_hash_bits(&self) -> Result<u8, &'static str> {
+    pub fn get_hash_bits(&self) -> Result<u8, Error> {
         if self.is_hash_list() {
             Ok(decode_hash_bits(self.harmonic_sum))
         } else {
-            Err("The counter is not in hash list mode.")
+            Err(Error::ModeMismatch {
+                expected: Mode::HashList,
+            })
         }
     }
 
@@ -54,14 +56,16 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
 
     #[inline]
     /// Returns the number of hashes stored in the harmonic sum.
-    /// 
+    ///
     /// # Errors
     /// If the counter is not in hash list mode, an error is returned.
-    pub fn get_number_of_hashes(&self) -> Result<u32, &'static str> {
+    pub fn get_number_of_hashes(&self) -> Result<u32, Error> {
         if self.is_hash_list() {
             Ok(decode_number_of_hashes(self.harmonic_sum))
         } else {
-            Err("The counter is not in hash list mode.")
+            Err(Error::ModeMismatch {
+                expected: Mode::HashList,
+            })
         }
     }
 }
@@ -97,18 +101,21 @@ fn decode_hash_bits(float: f64) -> u8 {
 const BITS_FOR_DUPLICATES: usize = 17;
 const DUPLICATES_OFFSET: usize = BITS_FOR_HASH_BITS;
 const DUPLICATES_MASK: u64 = (1 << BITS_FOR_DUPLICATES) - 1;
+/// The largest number of duplicates that can be stored.
+pub(crate) const MAXIMAL_DUPLICATES: u32 = (1 << BITS_FOR_DUPLICATES) - 1;
 
 #[allow(unsafe_code)]
 #[inline]
 /// Adds the count of duplicates to the harmonic sum.
 fn add_duplicates(float: &mut f64, new_duplicates: u32) {
-    set_duplicates(float, decode_duplicates(*float) + new_duplicates);
+    set_duplicates(float, decode_duplicates(*float).saturating_add(new_duplicates));
 }
 
 #[allow(unsafe_code)]
 #[inline]
+/// Sets the count of duplicates to the harmonic sum, saturating at [`MAXIMAL_DUPLICATES`].
 fn set_duplicates(float: &mut f64, duplicates: u32) {
-    assert!(u64::from(duplicates) <= DUPLICATES_MASK);
+    let duplicates = duplicates.min(MAXIMAL_DUPLICATES);
 
     let harmonic_sum_as_u64: &mut u64 = unsafe { core::mem::transmute(float) };
     *harmonic_sum_as_u64 = (*harmonic_sum_as_u64 & !(DUPLICATES_MASK << DUPLICATES_OFFSET))
@@ -150,6 +157,8 @@ fn decode_writer_tell(float: f64) -> u32 {
 const BITS_FOR_NUMBER_OF_HASHES: usize = 20;
 const NUMBER_OF_HASHES_OFFSET: usize = WRITER_TELL_OFFSET + BITS_FOR_WRITER_TELL;
 const NUMBER_OF_HASHES_MASK: u64 = (1 << BITS_FOR_NUMBER_OF_HASHES) - 1;
+/// The largest number of hashes that can be stored.
+pub(crate) const MAXIMAL_NUMBER_OF_HASHES: u32 = (1 << BITS_FOR_NUMBER_OF_HASHES) - 1;
 
 #[allow(unsafe_code)]
 #[inline]
@@ -200,6 +209,14 @@ mod test_encode_decode_hash_bits {
         assert_eq!(decode_duplicates(harmonic_sum), 3);
         add_duplicates(&mut harmonic_sum, 3);
         assert_eq!(decode_duplicates(harmonic_sum), 6);
+
+        // The duplicates saturate instead of overflowing into the other metadata.
+        set_writer_tell(&mut harmonic_sum, 42);
+        add_duplicates(&mut harmonic_sum, MAXIMAL_DUPLICATES);
+        assert_eq!(decode_duplicates(harmonic_sum), MAXIMAL_DUPLICATES);
+        add_duplicates(&mut harmonic_sum, u32::MAX);
+        assert_eq!(decode_duplicates(harmonic_sum), MAXIMAL_DUPLICATES);
+        assert_eq!(decode_writer_tell(harmonic_sum), 42);
     }
 
     #[test]
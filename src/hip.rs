@@ -0,0 +1,299 @@
+//! Submodule providing the [`HipHyperLogLog`] counter, which estimates the cardinality of a
+//! single stream of elements with the historic inverse probability (martingale) estimator.
+use crate::prelude::*;
+use core::hash::{BuildHasherDefault, Hash};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(serialize = "", deserialize = "Hasher: Default"))
+)]
+/// A [`HyperLogLog`] counter maintaining the historic inverse probability estimate of the cardinality.
+///
+/// # Implementative details
+/// Whenever an insertion increases a register, the counter is known to have seen a new element,
+/// which happened with probability equal to the harmonic sum of the registers divided by the
+/// number of registers. The running estimate is increased by the inverse of that probability,
+/// which is an unbiased estimate with a lower variance than the harmonic mean estimate. While
+/// the counter is a hash list, its usual estimate is employed, and the running estimate starts
+/// from the registers the hash list is converted to. The running estimate is only meaningful
+/// for counters that observed a single stream of elements: after a merge, or when built from
+/// a non-empty counter, the usual estimate is returned.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut hll: HipHyperLogLog<Precision10, Bits6, <Precision10 as PackedRegister<Bits6>>::Array> =
+///     Default::default();
+///
+/// hll.extend(0..50_000_u64);
+///
+/// let estimate = hll.estimate_cardinality();
+/// assert!(estimate > 48_000.0 && estimate < 52_000.0, "{estimate}");
+///
+/// // Merging an empty counter leaves the running estimate untouched.
+/// hll.merge(&HipHyperLogLog::default());
+/// assert!(!hll.is_merged());
+///
+/// let mut other: HipHyperLogLog<Precision10, Bits6, <Precision10 as PackedRegister<Bits6>>::Array> =
+///     Default::default();
+/// other.extend(50_000..60_000_u64);
+/// hll.merge(&other);
+///
+/// assert!(hll.is_merged());
+/// assert_eq!(hll.estimate_cardinality(), hll.as_ref().estimate_cardinality());
+/// ```
+pub struct HipHyperLogLog<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    Hasher: HasherType = BuildHasherDefault<twox_hash::XxHash64>,
+> {
+    /// The underlying counter.
+    counter: HyperLogLog<P, B, R, Hasher>,
+    /// The running estimate of the cardinality.
+    estimate: f64,
+    /// Whether the counter was merged with another counter, invalidating the running estimate.
+    merged: bool,
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> Default
+    for HipHyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::from(HyperLogLog::default())
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> From<HyperLogLog<P, B, R, H>>
+    for HipHyperLogLog<P, B, R, H>
+{
+    #[inline]
+    /// Wraps the provided counter, whose running estimate is only known when it is empty.
+    fn from(counter: HyperLogLog<P, B, R, H>) -> Self {
+        Self {
+            merged: !counter.is_empty(),
+            counter,
+            estimate: 0.0,
+        }
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> AsRef<HyperLogLog<P, B, R, H>>
+    for HipHyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn as_ref(&self) -> &HyperLogLog<P, B, R, H> {
+        &self.counter
+    }
+}
+
+impl<T: Hash, P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> Extend<T>
+    for HipHyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(&element);
+        }
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HipHyperLogLog<P, B, R, H> {
+    #[inline]
+    /// Inserts the element into the counter, returning whether the counter changed.
+    pub fn insert<T: Hash>(&mut self, element: &T) -> bool {
+        self.insert_hash(self.counter.hasher().hash_one(element))
+    }
+
+    #[inline]
+    /// Inserts the element with the provided hash into the counter, returning whether the counter changed.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, computed by the caller.
+    pub fn insert_hash(&mut self, hash: u64) -> bool {
+        if self.counter.is_hash_list() {
+            let changed = self.counter.insert_hash(hash);
+            if !self.counter.is_hash_list() {
+                // The running estimate starts from the registers the hash list was converted to.
+                self.estimate = self.counter.improved_estimate_cardinality();
+            }
+            return changed;
+        }
+
+        let (index, register, _) = HyperLogLog::<P, B, R, H>::index_and_register_from_hash(hash);
+        // The probability that a new element increases any register.
+        let probability = self.counter.harmonic_sum / f64::integer_exp2(P::EXPONENT);
+        let changed = self
+            .counter
+            .insert_register_value_and_index(register, index);
+        if changed {
+            self.estimate += 1.0 / probability;
+        }
+        changed
+    }
+
+    #[inline]
+    /// Merges the provided counter into the current one, after which the usual estimate is returned.
+    ///
+    /// # Implementative details
+    /// The running estimate is only invalidated when the merge changes the registers: merging a
+    /// counter already contained in the current one leaves it untouched, and while the result is
+    /// still a hash list the running estimate starts anew from the registers it is converted to.
+    pub fn merge(&mut self, other: &Self) {
+        let unchanged =
+            !self.counter.is_hash_list() && self.counter.may_contain_all(&other.counter);
+        self.counter.merge(&other.counter);
+        if !unchanged && !self.counter.is_hash_list() {
+            self.merged = true;
+        }
+    }
+
+    #[inline]
+    /// Returns whether the counter was merged, and therefore no longer maintains the running estimate.
+    pub fn is_merged(&self) -> bool {
+        self.merged
+    }
+
+    #[inline]
+    /// Returns the counter wrapped by the estimator.
+    pub fn into_inner(self) -> HyperLogLog<P, B, R, H> {
+        self.counter
+    }
+
+    #[inline]
+    /// Returns the estimate of the cardinality.
+    ///
+    /// # Implementative details
+    /// In registers mode, unless the counter was merged, the historic inverse probability
+    /// estimate is returned, while otherwise the usual estimate of the underlying counter is returned.
+    pub fn estimate_cardinality(&self) -> f64 {
+        if self.merged || self.counter.is_hash_list() {
+            self.counter.estimate_cardinality()
+        } else {
+            self.estimate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_estimator;
+    use twox_hash::XxHash;
+
+    #[test_estimator]
+    fn test_hip_estimate<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let mut hip_errors = 0.0;
+        let mut samples = 0.0;
+        let mut random_state = splitmix64(P::EXPONENT.into());
+
+        let number_of_registers = 1_u32 << P::EXPONENT;
+        // The hash lists of the highest precisions are too slow to fill one element at a time.
+        let number_of_elements = (4 * number_of_registers).min(50_000);
+
+        for _ in 0..5 {
+            random_state = splitmix64(random_state);
+            let mut hip = HipHyperLogLog::<P, B, R, H>::default();
+            let mut was_hash_list = true;
+
+            for element in 0..number_of_elements {
+                hip.insert(&(random_state ^ u64::from(element)));
+                // The running estimate is checked as it starts from the converted hash list,
+                // and once there are as many elements as registers, and four times as many.
+                let switched = was_hash_list && !hip.as_ref().is_hash_list();
+                was_hash_list = hip.as_ref().is_hash_list();
+                if !switched && ![number_of_registers, number_of_elements].contains(&(element + 1))
+                {
+                    continue;
+                }
+
+                if hip.as_ref().is_hash_list() {
+                    continue;
+                }
+                let exact = f64::from(element + 1);
+                hip_errors += (hip.estimate_cardinality() - exact).abs() / exact;
+                samples += 1.0;
+            }
+
+            // Merging a counter that changes no register keeps the running estimate.
+            let mut unchanged = hip.clone();
+            unchanged.merge(&HipHyperLogLog::default());
+            unchanged.merge(&hip);
+            assert!(!unchanged.is_merged());
+            assert_eq!(
+                unchanged.estimate_cardinality().to_bits(),
+                hip.estimate_cardinality().to_bits()
+            );
+
+            let mut other = HipHyperLogLog::<P, B, R, H>::default();
+            other.extend((0..number_of_elements).map(|element| !random_state ^ u64::from(element)));
+            let mut merged = hip.clone();
+            merged.merge(&other);
+            assert_eq!(merged.is_merged(), !merged.as_ref().is_hash_list());
+            assert!(!hip.is_merged());
+            assert_eq!(
+                merged.estimate_cardinality().to_bits(),
+                merged.as_ref().estimate_cardinality().to_bits()
+            );
+
+            // A union still fitting in the hash list keeps the estimate of the hash list.
+            let mut single = HipHyperLogLog::<P, B, R, H>::default();
+            single.insert(&random_state);
+            let mut merged = HipHyperLogLog::<P, B, R, H>::default();
+            merged.merge(&single);
+            assert!(merged.as_ref().is_hash_list());
+            assert!(!merged.is_merged());
+            assert_eq!(
+                merged.estimate_cardinality().to_bits(),
+                single.estimate_cardinality().to_bits()
+            );
+        }
+
+        if samples > 0.0 {
+            assert!(
+                hip_errors / samples <= P::error_rate(),
+                "HIP error rate ({}) is higher than expected ({}).",
+                hip_errors / samples,
+                P::error_rate()
+            );
+        }
+    }
+
+    #[test]
+    fn test_hip_from_counter() {
+        let mut counter = HyperLogLog::<
+            Precision8,
+            Bits6,
+            <Precision8 as PackedRegister<Bits6>>::Array,
+        >::default();
+        let hip = HipHyperLogLog::from(counter.clone());
+        assert!(!hip.is_merged());
+        assert!(hip.estimate_cardinality().abs() < f64::EPSILON);
+
+        counter.extend(0..10_u64);
+        let hip = HipHyperLogLog::from(counter.clone());
+        assert!(hip.is_merged());
+        assert_eq!(
+            hip.estimate_cardinality().to_bits(),
+            counter.estimate_cardinality().to_bits()
+        );
+
+        let mut hip = HipHyperLogLog::<
+            Precision8,
+            Bits6,
+            <Precision8 as PackedRegister<Bits6>>::Array,
+        >::default();
+        hip.extend(0..10_u64);
+        assert!(!hip.is_merged());
+        assert_eq!(
+            hip.estimate_cardinality().to_bits(),
+            counter.estimate_cardinality().to_bits()
+        );
+    }
+}
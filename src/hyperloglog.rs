@@ -1,39 +1,86 @@
 //! Marker struct for the hybrid approach, that keeps the hash explicit up until they fit into the registers.
 
+use crate::composite_hash::gaps::DispatchedDowngradedIter;
 use crate::composite_hash::{GapHash, SaturationError};
 use crate::correction_coefficients::{
     HASHLIST_CORRECTION_BIAS, HASHLIST_CORRECTION_CARDINALITIES, HYPERLOGLOG_CORRECTION_BIAS,
     HYPERLOGLOG_CORRECTION_CARDINALITIES,
 };
+use crate::estimate::{split_confidence, square_root};
 use crate::prelude::*;
+use crate::utils::UnionFromSortedIterators;
 use core::f64;
 use core::fmt::Debug;
-use core::hash::Hash;
+use core::hash::{BuildHasherDefault, Hash};
 use core::marker::PhantomData;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A struct representing the hybrid for approximate set cardinality estimation,
 /// where the hash values are kept explicit up until they fit into the registers.
 pub struct HyperLogLog<
     P: Precision,
     B: Bits,
     R: Registers<P, B>,
-    Hasher: HasherType = twox_hash::XxHash64,
+    Hasher: HasherType = BuildHasherDefault<twox_hash::XxHash64>,
 > {
     /// The registers of the counter.
     pub(crate) registers: R,
     /// The harmonic sum of the registers, i.e. the sum of 2^(-register_value) for all registers.
     pub(crate) harmonic_sum: f64,
+    /// The builder of the hashers used to hash the elements.
+    build_hasher: Hasher,
     /// Phantom data to ensure the type parameters are used.
-    _phantom: PhantomData<(P, B, Hasher)>,
+    _phantom: PhantomData<(P, B)>,
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> Default for HyperLogLog<P, B, R, H> {
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> Default
+    for HyperLogLog<P, B, R, H>
+{
     #[inline]
     fn default() -> Self {
-        Self::new()
+        Self::with_hasher(H::default())
+    }
+}
+
+/// The value hashed to identify the hasher builder of a counter.
+const HASHER_PROBE: &str = "hyperloglog-rs";
+
+#[cfg(not(feature = "alloc"))]
+/// Maximal number of elements hashed and inserted at once when inserting multiple elements without allocations.
+const INSERT_BATCH_SIZE: usize = 256;
+
+/// Minimal number of elements hashed and inserted at once when inserting multiple elements.
+const MINIMAL_INSERT_BATCH_SIZE: usize = 32;
+
+/// Fraction of the hashes in the hash list that are inserted at once when inserting multiple elements.
+const INSERT_BATCH_FRACTION: usize = 16;
+
+impl<T: Hash, P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> Extend<T>
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    /// Inserts the provided elements into the counter.
+    ///
+    /// # Panics
+    /// If the storage of the counter cannot hold the elements, which [`HyperLogLog::try_extend`] reports instead.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.try_extend(iter).unwrap();
+    }
+}
+
+impl<T: Hash, P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> FromIterator<T>
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    /// Returns a new counter containing the provided elements.
+    ///
+    /// # Panics
+    /// If the storage of the counter cannot hold the elements.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut hll = Self::default();
+        hll.extend(iter);
+        hll
     }
 }
 
@@ -48,6 +95,45 @@ pub fn correct_cardinality<P: Precision, B: Bits>(
     raw_estimate: f64,
     cardinalities: &[u32],
     biases: &[f64],
+) -> f64 {
+    correct_cardinality_with_tables::<P>(raw_estimate, cardinalities, biases)
+}
+
+#[inline]
+/// Returns the corrected estimate of the cardinality, if a correction is available.
+///
+/// # Arguments
+/// * `raw_estimate` - The raw estimate of the cardinality to correct.
+/// * `cardinalities` - The correction cardinalities, indexed by precision and bits.
+/// * `biases` - The correction biases, indexed by precision and bits.
+///
+/// # Implementative details
+/// The empirical corrections are only available up to precision 15: for larger
+/// precisions `None` is returned, and the caller should fall back to an estimate
+/// that requires no correction, such as the improved raw estimate.
+fn correct_cardinality_if_available<P: Precision, B: Bits>(
+    raw_estimate: f64,
+    cardinalities: &[[&[u32]; 3]],
+    biases: &[[&[f64]; 3]],
+) -> Option<f64> {
+    let precision_index = usize::from(P::EXPONENT) - 4;
+    let bits_index = usize::from(B::NUMBER_OF_BITS) - 4;
+    let cardinalities = cardinalities.get(precision_index)?[bits_index];
+    let biases = biases.get(precision_index)?[bits_index];
+
+    Some(correct_cardinality_with_tables::<P>(
+        raw_estimate,
+        cardinalities,
+        biases,
+    ))
+}
+
+#[inline]
+/// Returns the raw estimate corrected with the provided correction cardinalities and biases.
+fn correct_cardinality_with_tables<P: Precision>(
+    raw_estimate: f64,
+    cardinalities: &[u32],
+    biases: &[f64],
 ) -> f64 {
     if raw_estimate >= correction_upper_bound::<P>() {
         return raw_estimate;
@@ -87,12 +173,128 @@ pub fn correct_cardinality<P: Precision, B: Bits>(
         + lower_bias
 }
 
+#[inline]
+/// Returns the largest value a register may hold, i.e. the value of saturated registers.
+pub(crate) fn maximal_register_value<P: Precision, B: Bits>() -> u8 {
+    // Registers hold the number of leading zeros plus one of the hash bits not used
+    // for the index, capped at the largest value that fits in the register.
+    u8::try_from(B::MASK).unwrap().min(64 - P::EXPONENT)
+}
+
+#[inline]
+/// Returns the sigma function of the improved raw estimator, i.e. `x + sum_k x^(2^k) 2^(k-1)`.
+fn sigma(mut x: f64) -> f64 {
+    if x >= 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous_z = z;
+        z += x * y;
+        y += y;
+        if previous_z.to_bits() == z.to_bits() {
+            return z;
+        }
+    }
+}
+
+#[inline]
+/// Returns the tau function of the improved raw estimator, i.e.
+/// `(1 - x - sum_k (1 - x^(2^(-k)))^2 2^(-k)) / 3`.
+fn tau(mut x: f64) -> f64 {
+    if x <= 0.0 || x >= 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = square_root(x);
+        let previous_z = z;
+        y *= 0.5;
+        z -= (1.0 - x) * (1.0 - x) * y;
+        if previous_z.to_bits() == z.to_bits() {
+            return z / 3.0;
+        }
+    }
+}
+
+#[inline]
+/// Returns the improved raw estimate of the cardinality from the histogram of the register values.
+///
+/// # Arguments
+/// * `histogram` - The number of registers holding each register value.
+///
+/// # Implementative details
+/// The estimator, introduced by Otmar Ertl in "New cardinality estimation algorithms for
+/// `HyperLogLog` sketches", replaces the contributions of the empty and saturated registers
+/// to the harmonic sum with the sigma and tau functions, removing the bias of the raw
+/// estimate at small and large cardinalities without any empirical correction.
+pub(crate) fn improved_raw_estimate<P: Precision, B: Bits>(histogram: &[u32; 64]) -> f64 {
+    let q_plus_one = maximal_register_value::<P, B>();
+    let q = q_plus_one - 1;
+    let number_of_registers = f64::integer_exp2(P::EXPONENT);
+
+    let mut denominator = number_of_registers
+        * tau(1.0 - f64::from(histogram[usize::from(q_plus_one)]) / number_of_registers);
+    for register in (1..=q).rev() {
+        denominator = 0.5 * (denominator + f64::from(histogram[usize::from(register)]));
+    }
+    denominator += number_of_registers * sigma(f64::from(histogram[0]) / number_of_registers);
+
+    if denominator.is_infinite() {
+        return 0.0;
+    }
+
+    number_of_registers * number_of_registers / (2.0 * core::f64::consts::LN_2 * denominator)
+}
+
 impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B, R, H> {
     #[inline]
-    fn new() -> Self {
+    /// Returns a new empty counter, hashing the elements with hashers built by the provided builder.
+    ///
+    /// # Arguments
+    /// * `build_hasher` - The builder of the hashers used to hash the elements.
+    ///
+    /// # Implementative details
+    /// Counters built with different hasher builders, such as hashers with different
+    /// seeds, hash the same elements differently and must therefore not be combined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    /// use core::hash::{BuildHasher, Hasher};
+    ///
+    /// #[derive(Clone)]
+    /// struct SeededXxHash64(u64);
+    ///
+    /// impl BuildHasher for SeededXxHash64 {
+    ///     type Hasher = twox_hash::XxHash64;
+    ///
+    ///     fn build_hasher(&self) -> Self::Hasher {
+    ///         twox_hash::XxHash64::with_seed(self.0)
+    ///     }
+    /// }
+    ///
+    /// let mut hll: HyperLogLog<
+    ///     Precision8,
+    ///     Bits6,
+    ///     <Precision8 as PackedRegister<Bits6>>::Array,
+    ///     SeededXxHash64,
+    /// > = HyperLogLog::with_hasher(SeededXxHash64(42));
+    ///
+    /// hll.insert(&42);
+    ///
+    /// assert!(hll.may_contain(&42));
+    /// assert_eq!(hll.hasher().0, 42);
+    /// ```
+    pub fn with_hasher(build_hasher: H) -> Self {
         let mut hll = Self {
             registers: R::default(),
             harmonic_sum: f64::NEG_INFINITY,
+            build_hasher,
             _phantom: PhantomData,
         };
 
@@ -101,6 +303,24 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
         hll
     }
 
+    #[inline]
+    /// Returns an empty counter sharing the hasher builder of the current counter.
+    fn empty_like(&self) -> Self {
+        Self::with_hasher(self.build_hasher.clone())
+    }
+
+    #[inline]
+    /// Returns a reference to the builder of the hashers used to hash the elements.
+    pub fn hasher(&self) -> &H {
+        &self.build_hasher
+    }
+
+    #[inline]
+    /// Returns the identifier of the hasher builder, i.e. the hash of a fixed probe.
+    pub(crate) fn hasher_identifier(&self) -> u64 {
+        self.build_hasher.hash_one(HASHER_PROBE)
+    }
+
     #[inline]
     /// Returns whether the counter is empty.
     pub fn is_empty(&self) -> bool {
@@ -125,7 +345,51 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     #[inline]
     /// Returns whether the provided element may be contained in the counter.
     pub fn may_contain<T: Hash>(&self, element: &T) -> bool {
-        let (index, register, original_hash) = Self::index_and_register_and_hash(element);
+        let (index, register, original_hash) = self.index_and_register_and_hash(element);
+        self.may_contain_index_and_register(index, register, original_hash)
+    }
+
+    #[inline]
+    /// Returns whether the element with the provided hash may be contained in the counter.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, computed by the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll.insert_hash(0x9E37_79B9_7F4A_7C15);
+    ///
+    /// assert!(hll.may_contain_hash(0x9E37_79B9_7F4A_7C15));
+    /// assert!(!hll.may_contain_hash(0x94D0_49BB_1331_11EB));
+    /// ```
+    pub fn may_contain_hash(&self, hash: u64) -> bool {
+        let (index, register, original_hash) = Self::index_and_register_from_hash(hash);
+        self.may_contain_index_and_register(index, register, original_hash)
+    }
+
+    #[inline]
+    /// Returns whether all of the elements with the provided hashes may be contained in the counter.
+    ///
+    /// # Arguments
+    /// * `hashes` - The hashes of the elements, computed by the caller.
+    pub fn may_contain_all_hashes(&self, hashes: &[u64]) -> bool {
+        hashes.iter().all(|&hash| self.may_contain_hash(hash))
+    }
+
+    #[inline]
+    /// Returns whether the provided index and register, derived from the provided hash, may be contained in the counter.
+    fn may_contain_index_and_register(
+        &self,
+        index: usize,
+        register: u8,
+        original_hash: u64,
+    ) -> bool {
         if self.is_hash_list() {
             GapHash::<P, B>::find(
                 self.registers.as_ref(),
@@ -147,20 +411,28 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
         self.harmonic_sum.to_bits().leading_zeros() == 0
     }
 
+    #[inline]
+    /// Returns the mode the counter is currently in.
+    pub fn mode(&self) -> Mode {
+        if self.is_hash_list() {
+            Mode::HashList
+        } else {
+            Mode::Registers
+        }
+    }
+
     #[inline]
     /// Returns the number of registers equal to zero.
     ///
-    /// # Raises
-    /// If the counter is in HashList mode, an error is raised.
-    pub fn number_of_zero_registers(&self) -> Result<usize, &'static str> {
+    /// # Errors
+    /// If the counter is in hash list mode.
+    pub fn number_of_zero_registers(&self) -> Result<usize, Error> {
         if self.is_hash_list() {
-            Err("The counter is in HashList mode.")
+            Err(Error::ModeMismatch {
+                expected: Mode::Registers,
+            })
         } else {
-            Ok(self
-                .registers
-                .iter_registers()
-                .filter(|&register| register == 0)
-                .count())
+            Ok(self.registers.register_histogram()[0] as usize)
         }
     }
 
@@ -177,11 +449,125 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
 
     #[inline]
     /// Inserts an element into the counter.
+    ///
+    /// # Panics
+    /// If the storage of the counter cannot hold the element, which [`Self::try_insert`] reports instead.
     pub fn insert<T: Hash>(&mut self, element: &T) -> bool {
-        let (index, register, original_hash) = Self::index_and_register_and_hash(element);
+        let (index, register, original_hash) = self.index_and_register_and_hash(element);
+        self.insert_index_and_register(index, register, original_hash)
+    }
+
+    #[inline]
+    /// Inserts an element into the counter, returning an error when the counter cannot hold it.
+    ///
+    /// # Arguments
+    /// * `element` - The element to insert.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by the insertion.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the element.
+    /// * If the hash list metadata of the counter are not consistent with its mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Vec> =
+    ///     Default::default();
+    ///
+    /// assert_eq!(hll.try_insert(&42), Ok(true));
+    /// assert_eq!(hll.try_insert(&42), Ok(false));
+    /// assert!(hll.may_contain(&42));
+    /// ```
+    pub fn try_insert<T: Hash>(&mut self, element: &T) -> Result<bool, Error> {
+        let (index, register, original_hash) = self.index_and_register_and_hash(element);
+        self.try_insert_index_and_register(index, register, original_hash)
+    }
+
+    #[inline]
+    /// Inserts the element with the provided hash into the counter.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, computed by the caller.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by the insertion.
+    ///
+    /// # Implementative details
+    /// The provided hash is used in place of the one the counter would compute with its
+    /// hasher, so that hashes computed elsewhere result in identical counters. Inserting
+    /// the hash of an element is equivalent to inserting the element itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    /// use core::hash::{Hash, Hasher};
+    ///
+    /// let mut hll1: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut hll2: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// let mut hasher = twox_hash::XxHash64::default();
+    /// 42.hash(&mut hasher);
+    ///
+    /// assert!(hll1.insert_hash(hasher.finish()));
+    /// assert!(hll2.insert(&42));
+    ///
+    /// assert!(hll1.may_contain(&42));
+    /// assert_eq!(hll1.estimate_cardinality(), hll2.estimate_cardinality());
+    /// ```
+    pub fn insert_hash(&mut self, hash: u64) -> bool {
+        let (index, register, original_hash) = Self::index_and_register_from_hash(hash);
+        self.insert_index_and_register(index, register, original_hash)
+    }
+
+    #[inline]
+    /// Inserts the elements with the provided hashes into the counter.
+    ///
+    /// # Arguments
+    /// * `hashes` - The hashes of the elements, computed by the caller.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by any of the insertions.
+    ///
+    /// # Panics
+    /// If the storage of the counter cannot hold the elements.
+    pub fn insert_many_hashes(&mut self, hashes: &[u64]) -> bool {
+        self.insert_from_iter(hashes.iter().copied(), false)
+            .unwrap()
+    }
+
+    #[inline]
+    /// Inserts the provided index and register, derived from the provided hash, into the counter.
+    fn insert_index_and_register(
+        &mut self,
+        index: usize,
+        register: u8,
+        original_hash: u64,
+    ) -> bool {
+        self.try_insert_index_and_register(index, register, original_hash)
+            .unwrap()
+    }
+
+    #[inline]
+    /// Inserts the provided index and register, derived from the provided hash, into the counter.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the hash.
+    fn try_insert_index_and_register(
+        &mut self,
+        index: usize,
+        register: u8,
+        original_hash: u64,
+    ) -> Result<bool, Error> {
         if self.is_hash_list() {
-            let hash_bits = self.get_hash_bits().unwrap();
-            let number_of_hashes = self.get_number_of_hashes().unwrap();
+            let hash_bits = self.get_hash_bits()?;
+            let number_of_hashes = self.get_number_of_hashes()?;
             let writer_tell = self.get_writer_tell();
 
             match GapHash::<P, B>::insert_sorted_desc(
@@ -198,39 +584,311 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
                     self.set_writer_tell(insert_metadata.bit_index);
                     self.add_duplicates(insert_metadata.duplicates);
                     self.set_hash_bits(insert_metadata.hash_bits);
-                    true
+                    Ok(true)
                 }
-                Ok(None) => false,
+                Ok(None) => Ok(false),
                 Err(err) => match err {
                     SaturationError::ExtendableSaturation => {
-                        self.registers.increase_capacity();
-                        self.insert(element)
+                        self.registers.increase_capacity()?;
+                        self.try_insert_index_and_register(index, register, original_hash)
                     }
                     SaturationError::Saturation(bit_index) => {
                         self.set_writer_tell(bit_index);
                         debug_assert_eq!(bit_index, self.get_writer_tell());
-                        self.convert_hash_list_to_hyperloglog().unwrap();
+                        self.convert_hash_list_to_hyperloglog()?;
                         debug_assert!(!self.is_hash_list());
-                        self.insert(element)
+                        self.try_insert_index_and_register(index, register, original_hash)
                     }
                 },
             }
         } else {
-            self.insert_register_value_and_index(register, index)
+            Ok(self.insert_register_value_and_index(register, index))
         }
     }
 
     #[inline]
-    /// Converts the Hybrid counter to a regular [`HyperLogLog`] counter.
-    pub fn convert_hash_list_to_hyperloglog(&mut self) -> Result<(), &str> {
-        if !self.is_hash_list() {
-            return Err("The counter is already in HyperLogLog mode.");
+    /// Inserts the provided elements into the counter.
+    ///
+    /// # Arguments
+    /// * `elements` - The elements to insert.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by any of the insertions.
+    ///
+    /// # Implementative details
+    /// While the counter is in hash list mode, the elements are hashed in batches growing with
+    /// the hash list, which are sorted and merged into the hash list in a single pass, instead
+    /// of shifting the hash list once for each element. Each batch is encoded once, and the
+    /// hash list is rebuilt with the largest hash bits that fit. Whenever a batch does not fit
+    /// into the hash list, the counter is converted to registers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// assert!(hll.insert_many(&[42, 43, 44]));
+    /// assert!(!hll.insert_many(&[42, 43]));
+    ///
+    /// assert!(hll.may_contain(&42));
+    /// assert!(hll.may_contain(&44));
+    /// assert_eq!(hll.estimate_cardinality().round(), 3.0);
+    /// ```
+    ///
+    /// # Panics
+    /// If the storage of the counter cannot hold the elements, which [`Self::try_insert_many`] reports instead.
+    pub fn insert_many<T: Hash>(&mut self, elements: &[T]) -> bool {
+        self.try_insert_many(elements).unwrap()
+    }
+
+    #[inline]
+    /// Inserts the provided elements into the counter, returning an error when the counter cannot hold them.
+    ///
+    /// # Arguments
+    /// * `elements` - The elements to insert.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by any of the insertions.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the elements.
+    /// * If the hash list metadata of the counter are not consistent with its mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Vec> =
+    ///     Default::default();
+    ///
+    /// assert_eq!(hll.try_insert_many(&[42, 43, 44]), Ok(true));
+    /// assert_eq!(hll.try_insert_many(&[42, 43]), Ok(false));
+    /// assert!(hll.may_contain(&43));
+    /// ```
+    pub fn try_insert_many<T: Hash>(&mut self, elements: &[T]) -> Result<bool, Error> {
+        self.try_extend(elements)
+    }
+
+    #[inline]
+    /// Inserts the elements of the provided iterator into the counter, returning an error when
+    /// the counter cannot hold them.
+    ///
+    /// # Arguments
+    /// * `elements` - The elements to insert.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by any of the insertions.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the elements.
+    /// * If the hash list metadata of the counter are not consistent with its mode.
+    pub fn try_extend<T: Hash, I: IntoIterator<Item = T>>(
+        &mut self,
+        elements: I,
+    ) -> Result<bool, Error> {
+        let build_hasher = self.build_hasher.clone();
+        self.insert_from_iter(
+            elements
+                .into_iter()
+                .map(|element| build_hasher.hash_one(element)),
+            false,
+        )
+    }
+
+    #[inline]
+    /// Inserts the elements with the provided hashes into the counter, in batches.
+    ///
+    /// # Arguments
+    /// * `hashes` - The hashes of the elements to insert.
+    /// * `collisions_as_duplicates` - Whether hashes already present in the hash list belong to different elements.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the hashes.
+    /// * If the hash list metadata of the counter are not consistent with its mode.
+    ///
+    /// # Implementative details
+    /// Without allocations, the batches are further limited to a few hundred elements held on the stack.
+    fn insert_from_iter<I: Iterator<Item = u64>>(
+        &mut self,
+        mut hashes: I,
+        collisions_as_duplicates: bool,
+    ) -> Result<bool, Error> {
+        let mut modified = false;
+
+        #[cfg(feature = "alloc")]
+        let (mut batch, mut encoded) = (Vec::new(), Vec::new());
+        #[cfg(not(feature = "alloc"))]
+        let (mut batch, mut encoded) = ([0_u64; INSERT_BATCH_SIZE], [0_u32; INSERT_BATCH_SIZE]);
+
+        while self.is_hash_list() {
+            #[cfg(feature = "alloc")]
+            let batch_size = {
+                batch.clear();
+                batch.extend(hashes.by_ref().take(self.insert_batch_size()));
+                encoded.resize(batch.len(), 0);
+                batch.len()
+            };
+            #[cfg(not(feature = "alloc"))]
+            let batch_size = batch
+                .iter_mut()
+                .take(self.insert_batch_size())
+                .zip(hashes.by_ref())
+                .map(|(slot, hash)| *slot = hash)
+                .count();
+
+            if batch_size == 0 {
+                return Ok(modified);
+            }
+
+            modified |= self.insert_batch_into_hash_list(
+                &batch[..batch_size],
+                &mut encoded[..batch_size],
+                collisions_as_duplicates,
+            )?;
+        }
+
+        for hash in hashes {
+            let (index, register, _) = Self::index_and_register_from_hash(hash);
+            modified |= self.insert_register_value_and_index(register, index);
+        }
+        Ok(modified)
+    }
+
+    #[inline]
+    /// Returns the number of hashes to insert into the hash list in the next batch.
+    ///
+    /// # Implementative details
+    /// The batches grow with the hash list, so that it is rebuilt a logarithmic number of
+    /// times, while each batch remains small compared to the hash list. Since the new hashes
+    /// colliding with the downgraded ones are treated as already present, larger batches would
+    /// underestimate the duplicates with respect to inserting the hashes one by one.
+    fn insert_batch_size(&self) -> usize {
+        let number_of_hashes = usize::try_from(self.get_number_of_hashes().unwrap()).unwrap();
+        (number_of_hashes / INSERT_BATCH_FRACTION).max(MINIMAL_INSERT_BATCH_SIZE)
+    }
+
+    #[inline]
+    /// Inserts the provided batch of hashes into the hash list.
+    ///
+    /// # Arguments
+    /// * `batch` - The hashes of the elements to insert.
+    /// * `encoded` - The buffer where the encoded hashes are sorted, as long as the batch.
+    /// * `collisions_as_duplicates` - Whether hashes already present in the hash list belong to different elements.
+    ///
+    /// # Returns
+    /// Whether the counter was modified by the insertion.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the batch.
+    /// * If the counter is not in hash list mode.
+    fn insert_batch_into_hash_list(
+        &mut self,
+        batch: &[u64],
+        encoded: &mut [u32],
+        collisions_as_duplicates: bool,
+    ) -> Result<bool, Error> {
+        debug_assert_eq!(batch.len(), encoded.len());
+
+        let hash_bits = self.get_hash_bits()?;
+        let number_of_hashes = self.get_number_of_hashes()?;
+        let writer_tell = self.get_writer_tell();
+
+        for (encoded_hash, &hash) in encoded.iter_mut().zip(batch) {
+            let (index, register, original_hash) = Self::index_and_register_from_hash(hash);
+            *encoded_hash = GapHash::<P, B>::encode(index, register, original_hash, hash_bits);
+        }
+        encoded.sort_unstable_by(|a, b| b.cmp(a));
+        let encoded = &*encoded;
+
+        let union = || {
+            UnionFromSortedIterators::new(
+                GapHash::<P, B>::downgraded(
+                    self.registers.as_ref(),
+                    number_of_hashes,
+                    hash_bits,
+                    writer_tell,
+                    0,
+                ),
+                encoded.iter().copied(),
+            )
+        };
+
+        let union_size = u32::try_from(union().count()).unwrap();
+        let collisions = if collisions_as_duplicates {
+            number_of_hashes + u32::try_from(batch.len()).unwrap() - union_size
+        } else {
+            0
+        };
+
+        if union_size == number_of_hashes {
+            self.add_duplicates(collisions);
+            return Ok(false);
         }
-        let hash_bits = self.get_hash_bits().unwrap();
+
+        let mut updated = self.empty_like();
+        loop {
+            match GapHash::<P, B>::rebuild(updated.registers.as_mut(), union, union_size, hash_bits)
+            {
+                Ok(metadata) => {
+                    // As when inserting the hashes one by one, only the hashes already in the
+                    // hash list that collide once downgraded are counted as duplicates, while
+                    // the new hashes colliding with them are treated as already present.
+                    let duplicates = if collisions_as_duplicates || metadata.duplicates == 0 {
+                        metadata.duplicates
+                    } else {
+                        let downgraded_hashes = UnionFromSortedIterators::new(
+                            GapHash::<P, B>::downgraded(
+                                self.registers.as_ref(),
+                                number_of_hashes,
+                                hash_bits,
+                                writer_tell,
+                                hash_bits - metadata.hash_bits,
+                            ),
+                            core::iter::empty(),
+                        )
+                        .count();
+                        number_of_hashes - u32::try_from(downgraded_hashes).unwrap()
+                    };
+                    updated.set_number_of_hashes(union_size - metadata.duplicates);
+                    updated.set_writer_tell(metadata.bit_index);
+                    updated.set_hash_bits(metadata.hash_bits);
+                    updated.set_duplicates(self.get_duplicates() + collisions + duplicates);
+                    *self = updated;
+                    return Ok(true);
+                }
+                Err(SaturationError::ExtendableSaturation) => {
+                    updated.registers.increase_capacity()?;
+                }
+                Err(SaturationError::Saturation(_)) => {
+                    self.convert_hash_list_to_hyperloglog()?;
+                    for &hash in batch {
+                        let (index, register, _) = Self::index_and_register_from_hash(hash);
+                        self.insert_register_value_and_index(register, index);
+                    }
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    /// Converts the Hybrid counter to a regular [`HyperLogLog`] counter.
+    ///
+    /// # Errors
+    /// * If the counter is already in registers mode.
+    /// * If the storage of the counter cannot be extended to hold all of the registers.
+    pub fn convert_hash_list_to_hyperloglog(&mut self) -> Result<(), Error> {
+        let hash_bits = self.get_hash_bits()?;
+        let number_of_hashes = self.get_number_of_hashes()?;
+        // Registers backed by a vector may not yet have the capacity to hold all of the registers.
+        self.saturate_capacity()?;
         let mut new_registers = self.registers.clone();
         new_registers.clear_registers();
         let registers = core::mem::replace(&mut self.registers, new_registers);
-        let number_of_hashes = self.get_number_of_hashes().unwrap();
         let writer_tell = self.get_writer_tell();
         self.harmonic_sum = f64::integer_exp2(P::EXPONENT);
 
@@ -250,16 +908,464 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     }
 
     #[inline]
-    /// Splits a hash into a register value and an index.
-    fn insert_register_value_and_index(&mut self, new_register_value: u8, index: usize) -> bool {
-        // Count leading zeros.
-        debug_assert!(
-            new_register_value <= u8::try_from(B::MASK).unwrap(),
-            "Register value is too large: {new_register_value} > {}",
-            B::MASK
-        );
+    /// Grows the registers up to their maximal capacity, as required to switch to registers mode.
+    ///
+    /// # Errors
+    /// * If the registers cannot be extended up to their maximal capacity.
+    pub(crate) fn saturate_capacity(&mut self) -> Result<(), Error> {
+        while <R as AsRef<[u8]>>::as_ref(&self.registers).len() * 8 < R::bitsize() {
+            self.registers.increase_capacity()?;
+        }
+        Ok(())
+    }
 
-        let (old_register_value, larger_register_value) =
+    #[inline]
+    /// Merges the provided counter into the current one, which will then represent the union of the two sets.
+    ///
+    /// # Arguments
+    /// * `other` - The counter to merge into the current one.
+    ///
+    /// # Implementative details
+    /// When both counters are in hash list mode, the hashes of the two counters are merged into
+    /// a new hash list, downgrading the hash bits as needed to make them fit. Only when the hashes
+    /// do not fit with any of the viable hash bits the counter is converted to registers.
+    /// When any of the two counters is in registers mode, the result is in registers mode.
+    /// Both counters are expected to hash their elements with the same hasher builder.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll1: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut hll2: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll1.insert(&42);
+    /// hll2.insert(&43);
+    ///
+    /// hll1.merge(&hll2);
+    ///
+    /// assert!(hll1.may_contain(&42));
+    /// assert!(hll1.may_contain(&43));
+    /// assert_eq!(hll1.estimate_cardinality().round(), 2.0);
+    /// ```
+    ///
+    /// # Panics
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot hold the union.
+    ///
+    /// Both failures are reported by [`Self::try_merge`] instead.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.hasher_identifier(),
+            other.hasher_identifier(),
+            "The counters hash their elements with different hasher builders."
+        );
+        self.merge_counter(other).unwrap();
+    }
+
+    #[inline]
+    /// Merges the provided counter into the current one, returning an error when the counters
+    /// are incompatible or the union cannot be stored.
+    ///
+    /// # Arguments
+    /// * `other` - The counter to merge into the current one.
+    ///
+    /// # Errors
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot be extended to hold the union.
+    /// * If the hash list metadata of any of the counters are not consistent with its mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll1: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut hll2: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll1.insert(&42);
+    /// hll2.insert(&43);
+    ///
+    /// hll1.try_merge(&hll2).unwrap();
+    ///
+    /// assert!(hll1.may_contain(&42));
+    /// assert!(hll1.may_contain(&43));
+    /// ```
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), Error> {
+        if self.hasher_identifier() != other.hasher_identifier() {
+            return Err(Error::IncompatibleConfiguration(
+                "The counters hash their elements with different hasher builders.",
+            ));
+        }
+        self.merge_counter(other)
+    }
+
+    #[inline]
+    /// Merges the provided counter into the current one.
+    ///
+    /// # Errors
+    /// * If the storage of the counter cannot be extended to hold the union.
+    fn merge_counter(&mut self, other: &Self) -> Result<(), Error> {
+        match (self.is_hash_list(), other.is_hash_list()) {
+            (true, true) => {
+                if !self.merge_hash_lists(other)? {
+                    self.convert_hash_list_to_hyperloglog()?;
+                    self.insert_hash_list_into_registers(other)?;
+                }
+            }
+            (true, false) => {
+                let mut merged = other.clone();
+                merged.insert_hash_list_into_registers(self)?;
+                *self = merged;
+            }
+            (false, true) => {
+                self.insert_hash_list_into_registers(other)?;
+            }
+            (false, false) => {
+                for (index, register) in other.registers.iter_registers().enumerate() {
+                    self.insert_register_value_and_index(register, index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    /// Merges the hash list of the provided counter into the hash list of the current counter.
+    ///
+    /// # Returns
+    /// Whether the union of the two hash lists could be stored as a hash list, in which
+    /// case the current counter is updated, and is otherwise left unchanged.
+    ///
+    /// # Errors
+    /// * If the storage of the merged hash list cannot be extended.
+    fn merge_hash_lists(&mut self, other: &Self) -> Result<bool, Error> {
+        let left_hash_bits = self.get_hash_bits()?;
+        let right_hash_bits = other.get_hash_bits()?;
+        let left_number_of_hashes = self.get_number_of_hashes()?;
+        let right_number_of_hashes = other.get_number_of_hashes()?;
+        let hash_bits = left_hash_bits.min(right_hash_bits);
+
+        let left = || {
+            GapHash::<P, B>::downgraded(
+                self.registers.as_ref(),
+                left_number_of_hashes,
+                left_hash_bits,
+                self.get_writer_tell(),
+                left_hash_bits - hash_bits,
+            )
+        };
+        let right = || {
+            GapHash::<P, B>::downgraded(
+                other.registers.as_ref(),
+                right_number_of_hashes,
+                right_hash_bits,
+                other.get_writer_tell(),
+                right_hash_bits - hash_bits,
+            )
+        };
+        let union = || UnionFromSortedIterators::new(left(), right());
+
+        // Hashes of the same counter that collide once downgraded to the common hash
+        // bits belong to different elements, and are therefore counted as duplicates.
+        let collisions = count_contiguous_duplicates(left()) + count_contiguous_duplicates(right());
+        let number_of_hashes = u32::try_from(union().count()).unwrap();
+
+        let mut merged = self.empty_like();
+        loop {
+            match GapHash::<P, B>::rebuild(
+                merged.registers.as_mut(),
+                union,
+                number_of_hashes,
+                hash_bits,
+            ) {
+                Ok(metadata) => {
+                    merged.set_number_of_hashes(number_of_hashes - metadata.duplicates);
+                    merged.set_writer_tell(metadata.bit_index);
+                    merged.set_hash_bits(metadata.hash_bits);
+                    merged.set_duplicates(
+                        self.get_duplicates().max(other.get_duplicates())
+                            + collisions
+                            + metadata.duplicates,
+                    );
+                    *self = merged;
+                    return Ok(true);
+                }
+                Err(SaturationError::ExtendableSaturation) => {
+                    merged.registers.increase_capacity()?;
+                }
+                Err(SaturationError::Saturation(_)) => return Ok(false),
+            }
+        }
+    }
+
+    #[inline]
+    /// Inserts the hashes of the provided hash list counter into the registers of the current counter.
+    ///
+    /// # Errors
+    /// * If the current counter is not in registers mode, or the provided one is not a hash list.
+    fn insert_hash_list_into_registers(&mut self, other: &Self) -> Result<(), Error> {
+        if self.is_hash_list() {
+            return Err(Error::ModeMismatch {
+                expected: Mode::Registers,
+            });
+        }
+
+        let mut last_index = usize::MAX;
+        GapHash::<P, B>::decoded(
+            other.registers.as_ref(),
+            other.get_number_of_hashes()?,
+            other.get_hash_bits()?,
+            other.get_writer_tell(),
+        )
+        .for_each(|(new_register_value, index)| {
+            if last_index == index {
+                return;
+            }
+            last_index = index;
+            self.insert_register_value_and_index(new_register_value, index);
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    /// Returns the counter folded to the provided lower precision.
+    ///
+    /// # Arguments
+    /// * `P2` - The precision of the folded counter, which must not exceed the current precision.
+    ///
+    /// # Errors
+    /// * If the provided precision is higher than the current precision.
+    /// * If the storage of the counter cannot be extended to hold all of the registers.
+    ///
+    /// # Implementative details
+    /// In registers mode, each register is folded into the register sharing the lower bits
+    /// of its index: the discarded index bits extend the leading zeros run when the register
+    /// had no set bit in its hash. In hash list mode, the hashes are folded one by one, keeping
+    /// as many hash bits as the stored hashes allow so that the folded counter contains every
+    /// element of the original one, and counting the hashes that collide as duplicates.
+    /// Since fewer hash bits require prefix-free codes, which only suit saturated hash lists,
+    /// sparser hash lists are folded in registers mode. The folded counter is identical to a
+    /// counter built directly at the lower precision when both are in registers mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision10, Bits6, <Precision10 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll.insert(&42);
+    /// hll.insert(&43);
+    ///
+    /// let folded = hll
+    ///     .fold_to::<Precision8, <Precision8 as PackedRegister<Bits6>>::Array>()
+    ///     .unwrap();
+    ///
+    /// assert!(folded.may_contain(&42));
+    /// assert!(folded.may_contain(&43));
+    /// assert!(hll
+    ///     .fold_to::<Precision12, <Precision12 as PackedRegister<Bits6>>::Array>()
+    ///     .is_err());
+    /// ```
+    pub fn fold_to<P2: Precision, R2: Registers<P2, B>>(
+        &self,
+    ) -> Result<HyperLogLog<P2, B, R2, H>, Error> {
+        if P2::EXPONENT > P::EXPONENT {
+            return Err(Error::IncompatibleConfiguration(
+                "The precision of the folded counter must not exceed the current precision.",
+            ));
+        }
+
+        if !self.is_hash_list() {
+            let mut folded = HyperLogLog::<P2, B, R2, H>::with_hasher(self.build_hasher.clone());
+            folded.convert_hash_list_to_hyperloglog()?;
+            for (index, register) in self.registers.iter_registers().enumerate() {
+                if register == 0 {
+                    continue;
+                }
+                let (folded_index, folded_register, _) =
+                    HyperLogLog::<P2, B, R2, H>::index_and_register_from_hash(
+                        GapHash::<P, B>::restore_from_index_and_register(index, register),
+                    );
+                folded.insert_register_value_and_index(folded_register, folded_index);
+            }
+            return Ok(folded);
+        }
+
+        // The bits of the index dropped by the fold are no longer part of the folded hashes,
+        // so the folded hashes can only retain as many bits as were stored beyond them.
+        if let Some(folded) =
+            self.restore_hash_list(self.get_hash_bits()? - (P::EXPONENT - P2::EXPONENT))?
+        {
+            return Ok(folded);
+        }
+
+        let mut counter = self.clone();
+        counter.convert_hash_list_to_hyperloglog()?;
+        counter.fold_to()
+    }
+
+    #[inline]
+    /// Returns the counter converted to registers with the provided number of bits.
+    ///
+    /// # Errors
+    /// * If the registers with the provided number of bits do not fit in a byte.
+    /// * If the storage of the counter cannot be extended to hold all of the registers.
+    ///
+    /// # Implementative details
+    /// Widening the registers is lossless, while narrowing them clamps each register to the
+    /// largest value representable with the provided number of bits, exactly as a counter
+    /// built directly with the narrower registers would. Registers that were already clamped
+    /// before widening remain clamped. In hash list mode, the hashes are converted one by one,
+    /// keeping as many hash bits as the stored hashes allow, so that the converted counter
+    /// remains a hash list whenever its hashes fit. As when folding, sparser hash lists that
+    /// would require fewer hash bits are converted in registers mode, and the harmonic sum is
+    /// then recomputed from the converted registers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll.insert(&42);
+    /// hll.insert(&43);
+    ///
+    /// let narrowed = hll
+    ///     .convert_bits::<Bits4, <Precision8 as PackedRegister<Bits4>>::Array>()
+    ///     .unwrap();
+    /// let widened = narrowed
+    ///     .convert_bits::<Bits6, <Precision8 as PackedRegister<Bits6>>::Array>()
+    ///     .unwrap();
+    ///
+    /// assert!(narrowed.may_contain(&42));
+    /// assert!(narrowed.may_contain(&43));
+    /// assert!(widened.may_contain(&42));
+    /// assert!(widened.may_contain(&43));
+    /// ```
+    pub fn convert_bits<B2: Bits, R2: Registers<P, B2>>(
+        &self,
+    ) -> Result<HyperLogLog<P, B2, R2, H>, Error> {
+        if !self.is_hash_list() {
+            let mut converted = HyperLogLog::<P, B2, R2, H>::with_hasher(self.build_hasher.clone());
+            converted.convert_hash_list_to_hyperloglog()?;
+            let maximal_register = u8::try_from(B2::MASK).map_err(|_| {
+                Error::IncompatibleConfiguration(
+                    "The registers of the converted counter must fit in a byte.",
+                )
+            })?;
+            for (index, register) in self.registers.iter_registers().enumerate() {
+                if register > 0 {
+                    converted
+                        .insert_register_value_and_index(register.min(maximal_register), index);
+                }
+            }
+            return Ok(converted);
+        }
+
+        // Hashes with large registers store the register value in place of the upper bits
+        // of the hash, and the number of bits of the register value determines how many
+        // upper bits the converted hashes require. Hashes switching from the register value
+        // to the upper bits, which only happens when widening, know at least as many upper
+        // bits as leading zeros in their register, which are more than the register bits.
+        let hash_bits = self.get_hash_bits()?;
+        let hash_bits = if B2::NUMBER_OF_BITS <= B::NUMBER_OF_BITS {
+            hash_bits - (B::NUMBER_OF_BITS - B2::NUMBER_OF_BITS)
+        } else {
+            hash_bits
+                .min((hash_bits + 1 - B::NUMBER_OF_BITS).max(P::EXPONENT + B::NUMBER_OF_BITS + 3))
+        };
+
+        if let Some(converted) = self.restore_hash_list(hash_bits)? {
+            return Ok(converted);
+        }
+
+        let mut counter = self.clone();
+        counter.convert_hash_list_to_hyperloglog()?;
+        counter.convert_bits()
+    }
+
+    #[inline]
+    /// Returns a hash list counter with the provided configuration, containing the restored hashes
+    /// of the current hash list encoded with at most the provided hash bits.
+    ///
+    /// # Arguments
+    /// * `hash_bits` - The largest hash bits the restored hashes are known to be exact for.
+    ///
+    /// # Returns
+    /// The new counter, or `None` when the restored hashes cannot be stored as a hash list.
+    /// Hashes colliding in the new counter belong to different elements, and are therefore
+    /// counted as duplicates.
+    ///
+    /// # Errors
+    /// * If the counter is not in hash list mode.
+    fn restore_hash_list<P2: Precision, B2: Bits, R2: Registers<P2, B2>>(
+        &self,
+        hash_bits: u8,
+    ) -> Result<Option<HyperLogLog<P2, B2, R2, H>>, Error> {
+        let Some(restored_hash_bits) = GapHash::<P2, B2>::largest_viable_hash_bits(hash_bits)
+        else {
+            return Ok(None);
+        };
+        let hash_bits = self.get_hash_bits()?;
+        let number_of_hashes = self.get_number_of_hashes()?;
+
+        // The prefix-free codes are tuned for hash lists that saturated the explicit hashes,
+        // and therefore cannot encode the large gaps of sparser hash lists.
+        if restored_hash_bits < GapHash::<P2, B2>::LARGEST_VIABLE_HASH_BITS
+            && !GapHash::<P, B>::is_prefix_free_encoded(
+                number_of_hashes,
+                hash_bits,
+                self.get_writer_tell(),
+            )
+        {
+            return Ok(None);
+        }
+
+        let mut restored = HyperLogLog::<P2, B2, R2, H>::with_hasher(self.build_hasher.clone());
+        restored.set_hash_bits(restored_hash_bits);
+        restored.set_duplicates(self.get_duplicates());
+
+        restored.insert_from_iter(
+            GapHash::<P, B>::downgraded(
+                self.registers.as_ref(),
+                number_of_hashes,
+                hash_bits,
+                self.get_writer_tell(),
+                0,
+            )
+            .map(|hash| GapHash::<P, B>::restore(hash, hash_bits)),
+            true,
+        )?;
+
+        Ok(Some(restored))
+    }
+
+    #[inline]
+    /// Splits a hash into a register value and an index.
+    pub(crate) fn insert_register_value_and_index(
+        &mut self,
+        new_register_value: u8,
+        index: usize,
+    ) -> bool {
+        // Count leading zeros.
+        debug_assert!(
+            new_register_value <= u8::try_from(B::MASK).unwrap(),
+            "Register value is too large: {new_register_value} > {}",
+            B::MASK
+        );
+
+        let (old_register_value, larger_register_value) =
             self.registers.set_greater(index, new_register_value);
 
         self.harmonic_sum += f64::integer_exp2_minus(larger_register_value)
@@ -284,25 +1390,105 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     #[inline]
     /// Returns the corrected estimate of the cardinality.
     pub fn estimate_cardinality(&self) -> f64 {
-        if self.is_hash_list() {
-            correct_cardinality::<P, B>(
-                f64::from(self.get_number_of_hashes().unwrap() + self.get_duplicates()),
-                &HASHLIST_CORRECTION_CARDINALITIES[P::EXPONENT as usize - 4]
-                    [B::NUMBER_OF_BITS as usize - 4],
-                &HASHLIST_CORRECTION_BIAS[P::EXPONENT as usize - 4][B::NUMBER_OF_BITS as usize - 4],
+        let (cardinalities, biases) = if self.is_hash_list() {
+            (
+                &HASHLIST_CORRECTION_CARDINALITIES,
+                &HASHLIST_CORRECTION_BIAS,
             )
         } else {
-            let raw_estimate =
-                P::ALPHA * f64::integer_exp2(P::EXPONENT + P::EXPONENT) / self.harmonic_sum;
-
-            correct_cardinality::<P, B>(
-                raw_estimate,
-                &HYPERLOGLOG_CORRECTION_CARDINALITIES[P::EXPONENT as usize - 4]
-                    [B::NUMBER_OF_BITS as usize - 4],
-                &HYPERLOGLOG_CORRECTION_BIAS[P::EXPONENT as usize - 4]
-                    [B::NUMBER_OF_BITS as usize - 4],
+            (
+                &HYPERLOGLOG_CORRECTION_CARDINALITIES,
+                &HYPERLOGLOG_CORRECTION_BIAS,
             )
+        };
+
+        correct_cardinality_if_available::<P, B>(
+            self.uncorrected_estimate_cardinality(),
+            cardinalities,
+            biases,
+        )
+        .unwrap_or_else(|| self.improved_estimate_cardinality())
+    }
+
+    #[inline]
+    /// Returns the improved raw estimate of the cardinality, which requires no empirical correction.
+    ///
+    /// # Implementative details
+    /// In registers mode, the estimate is computed from the histogram of the register values
+    /// following Otmar Ertl's improved raw estimator, and is therefore available for all of the
+    /// precisions and bits. In hash list mode, the estimate is the number of hashes and duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision18, Bits6, <Precision18 as PackedRegister<Bits6>>::Vec> =
+    ///     Default::default();
+    ///
+    /// hll.extend(0..100_000_u64);
+    ///
+    /// let estimate = hll.improved_estimate_cardinality();
+    /// assert!(estimate > 99_000.0 && estimate < 101_000.0, "{estimate}");
+    /// ```
+    pub fn improved_estimate_cardinality(&self) -> f64 {
+        if self.is_hash_list() {
+            self.uncorrected_estimate_cardinality()
+        } else {
+            improved_raw_estimate::<P, B>(&self.register_histogram())
+        }
+    }
+
+    #[inline]
+    /// Returns the improved raw estimate of the union cardinality, which requires no empirical correction.
+    pub fn improved_estimate_union_cardinality(&self, other: &Self) -> f64 {
+        if self.is_hash_list() || other.is_hash_list() {
+            let mut union = self.clone();
+            union.merge(other);
+            return union.improved_estimate_cardinality();
         }
+
+        let mut histogram = [0_u32; 64];
+        for [left_register, right_register] in
+            self.registers.iter_registers_zipped(&other.registers)
+        {
+            histogram[usize::from(left_register.max(right_register))] += 1;
+        }
+        improved_raw_estimate::<P, B>(&histogram)
+    }
+
+    #[inline]
+    /// Returns the number of registers holding each register value.
+    ///
+    /// # Implementative details
+    /// In hash list mode, the histogram describes the registers the hash list would be converted to.
+    pub(crate) fn register_histogram(&self) -> [u32; 64] {
+        let (Ok(hash_bits), Ok(number_of_hashes)) =
+            (self.get_hash_bits(), self.get_number_of_hashes())
+        else {
+            return self.registers.register_histogram();
+        };
+
+        let mut histogram = [0_u32; 64];
+        // The hashes sharing an index are contiguous, and the first one has the largest register.
+        let mut last_index = usize::MAX;
+        let mut number_of_indices = 0;
+        for (register, index) in GapHash::<P, B>::decoded(
+            self.registers.as_ref(),
+            number_of_hashes,
+            hash_bits,
+            self.get_writer_tell(),
+        ) {
+            if last_index == index {
+                continue;
+            }
+            last_index = index;
+            histogram[usize::from(register)] += 1;
+            number_of_indices += 1;
+        }
+        histogram[0] = (1 << P::EXPONENT) - number_of_indices;
+
+        histogram
     }
 
     #[inline]
@@ -314,15 +1500,23 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     /// # Implementative details
     /// We define a counter that fully contains another counter when all of the registers
     /// of the first counter are greater than or equal to the corresponding registers of the second counter.
+    /// When both counters are hash lists, the hashes are downgraded to the smaller hash bits
+    /// and every hash of the second counter must appear among the hashes of the first one.
+    /// When only the second counter is a hash list, each of its hashes is decoded into its
+    /// index and register value, which must not exceed the register of the first counter.
+    /// When only the first counter is a hash list, its hashes are decoded in order of index,
+    /// so that the largest register value of each index must not be exceeded by the register
+    /// of the second counter, and every non-zero register of the second counter must be
+    /// matched by at least one of the hashes.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use hyperloglog_rs::prelude::*;
     ///
-    /// let mut hll1: PlusPlus<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    /// let mut hll1: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
     ///     Default::default();
-    /// let mut hll2: PlusPlus<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    /// let mut hll2: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
     ///     Default::default();
     ///
     /// hll1.insert(&42);
@@ -341,19 +1535,72 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     /// assert_eq!(hll2.may_contain_all(&hll1), true);
     /// ```
     pub fn may_contain_all(&self, rhs: &Self) -> bool {
-        self.registers
-            .iter_registers_zipped(&rhs.registers)
-            .all(|[left_register, right_register]| left_register >= right_register)
+        match (self.is_hash_list(), rhs.is_hash_list()) {
+            (true, true) => {
+                let (left, right) = self.downgraded_hash_lists(rhs);
+                contains_all_from_sorted_iterators(left, right)
+            }
+            (false, true) => GapHash::<P, B>::decoded(
+                rhs.registers.as_ref(),
+                rhs.get_number_of_hashes().unwrap(),
+                rhs.get_hash_bits().unwrap(),
+                rhs.get_writer_tell(),
+            )
+            .all(|(register, index)| self.registers.get_register(index) >= register),
+            (true, false) => {
+                // The hashes are sorted by their index, which is stored in their upper bits,
+                // so the hashes sharing an index are decoded one after the other.
+                let mut decoded = GapHash::<P, B>::decoded(
+                    self.registers.as_ref(),
+                    self.get_number_of_hashes().unwrap(),
+                    self.get_hash_bits().unwrap(),
+                    self.get_writer_tell(),
+                )
+                .peekable();
+                let mut matched_registers = 0;
+                while let Some((mut register, index)) = decoded.next() {
+                    while let Some((next_register, _)) =
+                        decoded.next_if(|&(_, next_index)| next_index == index)
+                    {
+                        register = register.max(next_register);
+                    }
+                    let rhs_register = rhs.registers.get_register(index);
+                    if rhs_register > register {
+                        return false;
+                    }
+                    if rhs_register > 0 {
+                        matched_registers += 1;
+                    }
+                }
+                rhs.registers
+                    .iter_registers()
+                    .filter(|&register| register > 0)
+                    .count()
+                    == matched_registers
+            }
+            (false, false) => self
+                .registers
+                .iter_registers_zipped(&rhs.registers)
+                .all(|[left_register, right_register]| left_register >= right_register),
+        }
     }
 
     #[inline]
     /// Hashes the element and returns the register value and the index of the register.
-    pub fn index_and_register_and_hash<T: Hash>(element: &T) -> (usize, u8, u64) {
-        let mut hasher = H::default();
-        element.hash(&mut hasher);
-        let hash = hasher.finish();
+    pub fn index_and_register_and_hash<T: Hash>(&self, element: &T) -> (usize, u8, u64) {
+        Self::index_and_register_from_hash(self.build_hasher.hash_one(element))
+    }
 
-        let index: usize = usize::try_from(hash & ((1 << P::EXPONENT) - 1)).unwrap();
+    #[inline]
+    #[must_use]
+    // The masked index has at most 18 bits and the register value is at most 65, so both fit.
+    #[allow(clippy::cast_possible_truncation)]
+    /// Returns the index of the register, the register value and the provided hash.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, computed by the caller.
+    pub fn index_and_register_from_hash(hash: u64) -> (usize, u8, u64) {
+        let index = (hash & ((1 << P::EXPONENT) - 1)) as usize;
 
         debug_assert!(
             index < 1 << P::EXPONENT,
@@ -372,10 +1619,10 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
             censored_hash |= 1_u64 << (64_u64 - <B as VariableWord>::MASK);
         }
 
-        let register_value = u8::try_from(censored_hash.leading_zeros() + 1).unwrap();
+        let register_value = (censored_hash.leading_zeros() + 1) as u8;
 
         debug_assert!(
-            register_value <= u8::try_from(<B as VariableWord>::MASK).unwrap(),
+            u64::from(register_value) <= <B as VariableWord>::MASK,
             "The register value {} must be less than or equal to the maximum register value {}.",
             register_value,
             (1 << <B as VariableWord>::NUMBER_OF_BITS) - 1
@@ -450,51 +1697,21 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
     }
 
     #[inline]
-    fn estimate_union_cardinality_with_cardinalities(
+    /// Returns the union cardinality, given the cardinalities of the two counters.
+    pub(crate) fn estimate_union_cardinality_with_cardinalities(
         &self,
         other: &Self,
         self_cardinality: f64,
         other_cardinality: f64,
     ) -> f64 {
+        debug_assert_eq!(
+            self.hasher_identifier(),
+            other.hasher_identifier(),
+            "The counters hash their elements with different hasher builders."
+        );
         match (self.is_hash_list(), other.is_hash_list()) {
             (true, true) => {
-                let left_hash_bits = self.get_hash_bits().unwrap();
-                let right_hash_bits = other.get_hash_bits().unwrap();
-                assert!(left_hash_bits >= GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS);
-                assert!(right_hash_bits >= GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS);
-
-                let left_shift = if left_hash_bits <= right_hash_bits {
-                    0
-                } else {
-                    left_hash_bits - right_hash_bits
-                };
-                let right_shift = if right_hash_bits <= left_hash_bits {
-                    0
-                } else {
-                    right_hash_bits - left_hash_bits
-                };
-
-                let left_hashes = self.registers.as_ref();
-                let right_hashes = other.registers.as_ref();
-                let left_bit_index = self.get_writer_tell();
-                let right_bit_index = other.get_writer_tell();
-
-                let intersection_cardinality = f64::from(intersection_from_sorted_iterators(
-                    GapHash::<P, B>::downgraded(
-                        left_hashes,
-                        self.get_number_of_hashes().unwrap(),
-                        left_hash_bits,
-                        left_bit_index,
-                        left_shift,
-                    ),
-                    GapHash::<P, B>::downgraded(
-                        right_hashes,
-                        other.get_number_of_hashes().unwrap(),
-                        right_hash_bits,
-                        right_bit_index,
-                        right_shift,
-                    ),
-                ));
+                let intersection_cardinality = f64::from(self.hash_lists_intersection_size(other));
 
                 let union_cardinality =
                     self_cardinality + other_cardinality - intersection_cardinality;
@@ -505,11 +1722,14 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
                 let hash_bits = self.get_hash_bits().unwrap();
                 assert!(hash_bits >= GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS);
 
-                self.union_estimation_from_sorted_iterator_and_counter(
-                    other,
-                    self_cardinality,
-                    other_cardinality,
+                let union_estimate = correct_cardinality_if_available::<P, B>(
+                    P::ALPHA * f64::integer_exp2(P::EXPONENT + P::EXPONENT)
+                        / self.union_harmonic_sum_from_sorted_iterator_and_counter(other),
+                    &HYPERLOGLOG_CORRECTION_CARDINALITIES,
+                    &HYPERLOGLOG_CORRECTION_BIAS,
                 )
+                .unwrap_or_else(|| self.improved_estimate_union_cardinality(other));
+                correct_union_estimate(self_cardinality, other_cardinality, union_estimate)
             }
             (false, true) => other.estimate_union_cardinality_with_cardinalities(
                 self,
@@ -517,31 +1737,598 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B,
                 other_cardinality,
             ),
             (false, false) => {
-                let union_estimate = correct_cardinality::<P, B>(
+                let union_estimate = correct_cardinality_if_available::<P, B>(
                     P::ALPHA * f64::integer_exp2(P::EXPONENT + P::EXPONENT)
                         / self.registers.get_union_harmonic_sum(&other.registers),
-                    &HYPERLOGLOG_CORRECTION_CARDINALITIES[P::EXPONENT as usize - 4]
-                        [B::NUMBER_OF_BITS as usize - 4],
-                    &HYPERLOGLOG_CORRECTION_BIAS[P::EXPONENT as usize - 4]
-                        [B::NUMBER_OF_BITS as usize - 4],
-                );
+                    &HYPERLOGLOG_CORRECTION_CARDINALITIES,
+                    &HYPERLOGLOG_CORRECTION_BIAS,
+                )
+                .unwrap_or_else(|| self.improved_estimate_union_cardinality(other));
                 correct_union_estimate(self_cardinality, other_cardinality, union_estimate)
             }
         }
     }
-}
 
-#[cfg(test)]
-mod test_hybrid_propertis {
-    use super::*;
-    use hyperloglog_derive::test_estimator;
-    use twox_hash::XxHash;
+    #[inline]
+    /// Returns the number of hashes shared by the two hash lists, downgraded to the smaller hash bits.
+    pub(crate) fn hash_lists_intersection_size(&self, other: &Self) -> u32 {
+        let (left, right) = self.downgraded_hash_lists(other);
+        intersection_from_sorted_iterators(left, right)
+    }
 
-    #[test_estimator]
-    fn test_plusplus_properties<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
-        let mut hybrid: HyperLogLog<P, B, R, H> = Default::default();
-        assert!(hybrid.is_hash_list());
-        assert!(hybrid.is_empty());
+    #[inline]
+    /// Returns the iterators over the hashes of the two hash lists, downgraded to the smaller hash bits.
+    fn downgraded_hash_lists<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> (
+        DispatchedDowngradedIter<'a, P, B>,
+        DispatchedDowngradedIter<'a, P, B>,
+    ) {
+        debug_assert!(self.is_hash_list());
+        debug_assert!(other.is_hash_list());
+
+        let left_hash_bits = self.get_hash_bits().unwrap();
+        let right_hash_bits = other.get_hash_bits().unwrap();
+        assert!(left_hash_bits >= GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS);
+        assert!(right_hash_bits >= GapHash::<P, B>::SMALLEST_VIABLE_HASH_BITS);
+
+        let left_shift = left_hash_bits.saturating_sub(right_hash_bits);
+        let right_shift = right_hash_bits.saturating_sub(left_hash_bits);
+
+        (
+            GapHash::<P, B>::downgraded(
+                self.registers.as_ref(),
+                self.get_number_of_hashes().unwrap(),
+                left_hash_bits,
+                self.get_writer_tell(),
+                left_shift,
+            ),
+            GapHash::<P, B>::downgraded(
+                other.registers.as_ref(),
+                other.get_number_of_hashes().unwrap(),
+                right_hash_bits,
+                other.get_writer_tell(),
+                right_shift,
+            ),
+        )
+    }
+
+    #[inline]
+    /// Returns the estimate of the cardinality, with the interval expected to contain the
+    /// exact cardinality with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `confidence` - The probability that the interval contains the exact cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// In registers mode, the interval spans the standard error of the precision, as provided
+    /// by [`Precision::error_rate`]. In hash list mode, every hash belongs to at least one
+    /// element, so the cardinality is at least the number of hashes. It exceeds the number of
+    /// hashes and duplicates, i.e. the hashes known to have collided while downgrading the hash
+    /// list, only by the unnoticed collisions between the hashes of distinct elements, which
+    /// are rare with the larger hash bits, making the interval almost exact. Once duplicates
+    /// are known, the upper bound also spans the standard error of the precision.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll.insert(&42);
+    /// hll.insert(&43);
+    ///
+    /// let estimate = hll.estimate_cardinality_with_confidence(0.95);
+    ///
+    /// assert_eq!(estimate.value, hll.estimate_cardinality());
+    /// assert_eq!(estimate.confidence, 0.95);
+    /// assert!(estimate.contains(2.0));
+    /// assert!(estimate.width() < 1.0);
+    /// ```
+    pub fn estimate_cardinality_with_confidence(&self, confidence: f64) -> Estimate {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "The confidence ({confidence}) must be strictly between zero and one."
+        );
+
+        let value = self.estimate_cardinality();
+
+        if !self.is_hash_list() {
+            return Estimate::from_relative_error(value, P::error_rate(), confidence);
+        }
+
+        Estimate::from_hashes(
+            value,
+            self.get_number_of_hashes().unwrap(),
+            self.get_duplicates(),
+            self.get_hash_bits().unwrap(),
+            B::NUMBER_OF_BITS,
+            P::error_rate(),
+            confidence,
+        )
+    }
+
+    #[inline]
+    /// Returns the estimate of the union cardinality between two counters, with the interval
+    /// expected to contain the exact union cardinality with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the exact union cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// When both counters are hash lists, the union contains at least the distinct hashes of
+    /// the two counters, and exceeds them only by the duplicates of the two counters and the
+    /// unnoticed collisions. Otherwise, the interval spans the standard error of the precision.
+    pub fn estimate_union_cardinality_with_confidence(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> Estimate {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "The confidence ({confidence}) must be strictly between zero and one."
+        );
+
+        self.estimate_union_cardinality_with_confidence_and_cardinalities(
+            other,
+            confidence,
+            self.estimate_cardinality(),
+            other.estimate_cardinality(),
+        )
+    }
+
+    #[inline]
+    fn estimate_union_cardinality_with_confidence_and_cardinalities(
+        &self,
+        other: &Self,
+        confidence: f64,
+        self_cardinality: f64,
+        other_cardinality: f64,
+    ) -> Estimate {
+        let value = self.estimate_union_cardinality_with_cardinalities(
+            other,
+            self_cardinality,
+            other_cardinality,
+        );
+
+        if !self.is_hash_list() || !other.is_hash_list() {
+            return Estimate::from_relative_error(value, P::error_rate(), confidence);
+        }
+
+        let self_hashes = self.get_number_of_hashes().unwrap();
+        let other_hashes = other.get_number_of_hashes().unwrap();
+        let hash_bits = self
+            .get_hash_bits()
+            .unwrap()
+            .min(other.get_hash_bits().unwrap());
+        let shared_hashes = self.hash_lists_intersection_size(other);
+        let union_hashes = self_hashes + other_hashes - shared_hashes;
+
+        Estimate::from_hashes(
+            value,
+            union_hashes,
+            self.get_duplicates() + other.get_duplicates(),
+            hash_bits,
+            B::NUMBER_OF_BITS,
+            P::error_rate(),
+            confidence,
+        )
+    }
+
+    #[inline]
+    /// Returns the estimates of the cardinalities of the two counters and of their union,
+    /// such that all of their intervals contain the exact values with the provided confidence.
+    fn estimate_cardinalities_with_confidence(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> (Estimate, Estimate, Estimate) {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "The confidence ({confidence}) must be strictly between zero and one."
+        );
+
+        let split = split_confidence(confidence, 3);
+        let self_estimate = self.estimate_cardinality_with_confidence(split);
+        let other_estimate = other.estimate_cardinality_with_confidence(split);
+        let union_estimate = self.estimate_union_cardinality_with_confidence_and_cardinalities(
+            other,
+            split,
+            self_estimate.value,
+            other_estimate.value,
+        );
+
+        (self_estimate, other_estimate, union_estimate)
+    }
+
+    #[inline]
+    /// Returns the estimate of the intersection cardinality between two counters, with the
+    /// interval expected to contain the exact intersection cardinality with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the exact intersection cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// The intersection cardinality is obtained by inclusion-exclusion from the cardinalities
+    /// of the two counters and of their union. As the three estimates are computed from the
+    /// same registers, their errors are correlated, and the bounds of the interval are
+    /// therefore combined linearly rather than in quadrature. Each of the three intervals
+    /// is computed with a confidence such that all of them hold with the provided confidence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll1: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut hll2: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// hll1.insert_many(&[1, 2, 3, 4]);
+    /// hll2.insert_many(&[3, 4, 5]);
+    ///
+    /// let estimate = hll1.estimate_intersection_cardinality_with_confidence(&hll2, 0.95);
+    ///
+    /// assert_eq!(estimate.value, hll1.estimate_intersection_cardinality(&hll2));
+    /// assert!(estimate.contains(2.0));
+    /// ```
+    pub fn estimate_intersection_cardinality_with_confidence(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> Estimate {
+        let (self_estimate, other_estimate, union_estimate) =
+            self.estimate_cardinalities_with_confidence(other, confidence);
+
+        Self::intersection_from_estimates(self_estimate, other_estimate, union_estimate, confidence)
+    }
+
+    #[inline]
+    /// Returns the intersection estimate obtained by inclusion-exclusion from the provided estimates.
+    fn intersection_from_estimates(
+        self_estimate: Estimate,
+        other_estimate: Estimate,
+        union_estimate: Estimate,
+        confidence: f64,
+    ) -> Estimate {
+        Estimate::new(
+            (self_estimate.value + other_estimate.value - union_estimate.value).max(0.0),
+            (self_estimate.lower + other_estimate.lower - union_estimate.upper).max(0.0),
+            (self_estimate.upper + other_estimate.upper - union_estimate.lower)
+                .min(self_estimate.upper)
+                .min(other_estimate.upper),
+            confidence,
+        )
+    }
+
+    #[inline]
+    /// Returns the estimate of the Jaccard index between two counters, with the interval
+    /// expected to contain the exact Jaccard index with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the exact Jaccard index.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// The bounds are the ratios of the bounds of the intersection cardinality, obtained as in
+    /// [`HyperLogLog::estimate_intersection_cardinality_with_confidence`], and of the opposite
+    /// bounds of the union cardinality.
+    pub fn estimate_jaccard_index_with_confidence(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> Estimate {
+        let (self_estimate, other_estimate, union_estimate) =
+            self.estimate_cardinalities_with_confidence(other, confidence);
+        let intersection_estimate = Self::intersection_from_estimates(
+            self_estimate,
+            other_estimate,
+            union_estimate,
+            confidence,
+        );
+
+        let value = if union_estimate.value.is_zero() {
+            0.0
+        } else {
+            intersection_estimate.value / union_estimate.value
+        };
+        let lower = if union_estimate.upper.is_zero() {
+            0.0
+        } else {
+            intersection_estimate.lower / union_estimate.upper
+        };
+        let upper = if union_estimate.lower.is_zero() {
+            1.0
+        } else {
+            (intersection_estimate.upper / union_estimate.lower).min(1.0)
+        };
+
+        Estimate::new(value, lower, upper, confidence)
+    }
+
+    #[inline]
+    /// Returns the estimate of the cardinality of the current counter minus the cardinality of
+    /// the other counter, with the interval expected to contain the exact difference cardinality
+    /// with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the exact difference cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// The difference cardinality is obtained as the union cardinality minus the cardinality
+    /// of the other counter, combining the bounds of the two as for the intersection, and it
+    /// cannot exceed the cardinality of the current counter.
+    pub fn estimate_difference_cardinality_with_confidence(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> Estimate {
+        let (self_estimate, other_estimate, union_estimate) =
+            self.estimate_cardinalities_with_confidence(other, confidence);
+
+        Estimate::new(
+            (union_estimate.value - other_estimate.value).max(0.0),
+            (union_estimate.lower - other_estimate.upper).max(0.0),
+            (union_estimate.upper - other_estimate.lower).min(self_estimate.upper),
+            confidence,
+        )
+    }
+
+    #[inline]
+    /// Returns the estimate of the fraction of the elements of the current counter that are
+    /// also in the other counter, with the interval expected to contain the exact fraction
+    /// with the provided confidence.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter, usually representing a larger set.
+    /// * `confidence` - The probability that the interval contains the exact fraction.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    ///
+    /// # Implementative details
+    /// When the current counter is a hash list and the other one is not, each of the hashes
+    /// is checked against the registers of the other counter, as in [`HyperLogLog::may_contain`].
+    /// A hash not in the other counter passes the check with the probability that the register
+    /// at its index is at least as large as its register value, so the number of hashes passing
+    /// the check by chance is subtracted from the ones that do. The interval spans the standard
+    /// error of the binomial number of hashes passing by chance, which grows as the registers of
+    /// the other counter fill up. Otherwise, the fraction is the ratio of the intersection
+    /// cardinality, obtained as in [`HyperLogLog::estimate_intersection_cardinality_with_confidence`],
+    /// and of the cardinality of the current counter. An empty counter has an undetermined
+    /// fraction, spanning the whole unit interval.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut small: HyperLogLog<Precision14, Bits6, <Precision14 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut big: HyperLogLog<Precision14, Bits6, <Precision14 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    ///
+    /// // Half of the elements of the small set are in the big one.
+    /// small.extend(4_900..5_100_u64);
+    /// big.extend(0..5_000_u64);
+    /// big.convert_hash_list_to_hyperloglog().unwrap();
+    ///
+    /// let containment = small.estimate_containment(&big, 0.95);
+    ///
+    /// assert!(containment.contains(0.5), "{containment:?}");
+    /// assert!(containment.width() < 0.3, "{containment:?}");
+    /// ```
+    pub fn estimate_containment(&self, other: &Self, confidence: f64) -> Estimate {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "The confidence ({confidence}) must be strictly between zero and one."
+        );
+
+        if !self.is_hash_list() || other.is_hash_list() {
+            let (self_estimate, other_estimate, union_estimate) =
+                self.estimate_cardinalities_with_confidence(other, confidence);
+            let intersection_estimate = Self::intersection_from_estimates(
+                self_estimate,
+                other_estimate,
+                union_estimate,
+                confidence,
+            );
+
+            if self_estimate.value.is_zero() {
+                return Estimate::new(0.0, 0.0, 1.0, confidence);
+            }
+            let lower = if self_estimate.upper.is_zero() {
+                0.0
+            } else {
+                intersection_estimate.lower / self_estimate.upper
+            };
+            let upper = if self_estimate.lower.is_zero() {
+                1.0
+            } else {
+                intersection_estimate.upper / self_estimate.lower
+            };
+
+            return Estimate::new(
+                (intersection_estimate.value / self_estimate.value).min(1.0),
+                lower.min(1.0),
+                upper.min(1.0),
+                confidence,
+            );
+        }
+
+        let (passed, number_of_hashes, false_positives) =
+            self.containment_from_sorted_iterator_and_counter(other);
+
+        if number_of_hashes == 0 {
+            return Estimate::new(0.0, 0.0, 1.0, confidence);
+        }
+
+        let number_of_hashes = f64::from(number_of_hashes);
+        let false_positive_rate = false_positives / number_of_hashes;
+
+        // When all of the hashes pass the check by chance, the check carries no information.
+        if false_positive_rate >= 1.0 {
+            return Estimate::new(1.0, 0.0, 1.0, confidence);
+        }
+
+        let contained = ((f64::from(passed) - false_positives) / (1.0 - false_positive_rate))
+            .clamp(0.0, number_of_hashes);
+        let standard_error = square_root(
+            (number_of_hashes - contained) * false_positive_rate / (1.0 - false_positive_rate),
+        ) / number_of_hashes;
+        let estimate =
+            Estimate::from_standard_error(contained / number_of_hashes, standard_error, confidence);
+
+        Estimate::new(
+            estimate.value,
+            estimate.lower,
+            estimate.upper.min(1.0),
+            confidence,
+        )
+    }
+}
+
+#[inline]
+/// Returns the number of values equal to their predecessor in the provided iterator.
+fn count_contiguous_duplicates<I: Iterator<Item = u32>>(iter: I) -> u32 {
+    let mut previous = None;
+    let mut duplicates = 0;
+    for value in iter {
+        duplicates += u32::from(previous == Some(value));
+        previous = Some(value);
+    }
+    duplicates
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> BitOrAssign<&Self>
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    /// Merges the provided counter into the current one.
+    ///
+    /// # Panics
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot hold the union.
+    fn bitor_assign(&mut self, rhs: &Self) {
+        self.merge(rhs);
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> BitOrAssign
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    /// Merges the provided counter into the current one.
+    ///
+    /// # Panics
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot hold the union.
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.merge(&rhs);
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> BitOr for HyperLogLog<P, B, R, H> {
+    type Output = Self;
+
+    #[inline]
+    /// Returns the union of the two counters.
+    ///
+    /// # Panics
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot hold the union.
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self.merge(&rhs);
+        self
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> BitOr for &HyperLogLog<P, B, R, H> {
+    type Output = HyperLogLog<P, B, R, H>;
+
+    #[inline]
+    /// Returns the union of the two counters.
+    ///
+    /// # Panics
+    /// * If the two counters hash their elements with different hasher builders.
+    /// * If the storage of the counter cannot hold the union.
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut union = self.clone();
+        union.merge(rhs);
+        union
+    }
+}
+
+#[cfg(test)]
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> HyperLogLog<P, B, R, H> {
+    /// Returns the largest number of the provided elements, inserted in order, that fit in the hash list.
+    ///
+    /// # Implementative details
+    /// The search stops at ten thousand elements, since filling the hash lists of the
+    /// highest precisions is too slow for the tests.
+    pub(crate) fn hash_list_capacity(element: impl Fn(u64) -> u64) -> u64 {
+        let limit = 10_000;
+        let mut fitting = Self::default();
+        let mut fitting_size = 0;
+        let mut step = 1;
+        let mut growing = true;
+
+        // The step doubles until the hash list overflows, and then halves as in a binary search.
+        while step > 0 {
+            let mut candidate = fitting.clone();
+            candidate.extend((fitting_size..fitting_size + step).map(&element));
+            if candidate.is_hash_list() {
+                fitting = candidate;
+                fitting_size += step;
+            } else {
+                growing = false;
+            }
+            step = if growing { step * 2 } else { step / 2 }.min(limit - fitting_size);
+        }
+
+        fitting_size
+    }
+}
+
+#[cfg(test)]
+mod test_hybrid_propertis {
+    use super::*;
+    use core::hash::BuildHasher;
+    use hyperloglog_derive::{test_estimator, test_precisions_and_bits};
+    use twox_hash::XxHash;
+
+    #[test_estimator]
+    fn test_plusplus_properties<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let mut hybrid: HyperLogLog<P, B, R, H> = Default::default();
+        assert!(hybrid.is_hash_list());
+        assert!(hybrid.is_empty());
         assert!(!hybrid.is_full());
         assert_eq!(hybrid.get_number_of_hashes().unwrap(), 0);
         let mut normalized_error = 0.0;
@@ -584,4 +2371,804 @@ mod test_hybrid_propertis {
 
         assert!(!hybrid.is_hash_list());
     }
+
+    #[test_estimator]
+    fn test_may_contain_all<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        // Each of the counters may be either in hash list or in registers mode.
+        let sizes = [0, 1, capacity, capacity + 1];
+
+        for big_size in sizes {
+            let mut big = HyperLogLog::<P, B, R, H>::default();
+            big.extend((0..big_size).map(element));
+
+            for small_size in sizes.into_iter().filter(|&size| size <= big_size) {
+                // The small counter holds the first elements of the big counter.
+                let mut small = HyperLogLog::<P, B, R, H>::default();
+                small.extend((0..small_size).map(element));
+
+                assert!(
+                    big.may_contain_all(&small),
+                    "The big counter must contain the small one. Big size: {big_size}, small size: {small_size}."
+                );
+
+                // An element that the big counter cannot contain must be detected, unless
+                // the hash list has to be converted to registers to be compared.
+                let mut extended = small.clone();
+                extended.insert(&element(big_size));
+                if !big.may_contain(&element(big_size))
+                    && (extended.is_hash_list() || !big.is_hash_list())
+                {
+                    assert!(
+                        !big.may_contain_all(&extended),
+                        "The big counter must not contain the extended one. Big size: {big_size}, small size: {small_size}."
+                    );
+                }
+            }
+        }
+    }
+
+    #[test_estimator]
+    fn test_may_contain_all_from_hash_list<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let mut hash_list = HyperLogLog::<P, B, R, H>::default();
+
+        for size in (0..200_u64).step_by(20) {
+            hash_list.extend((size.saturating_sub(20)..size).map(element));
+            if !hash_list.is_hash_list() {
+                break;
+            }
+
+            let mut registers = hash_list.clone();
+            registers.convert_hash_list_to_hyperloglog().unwrap();
+            assert!(hash_list.may_contain_all(&registers));
+
+            // Comparing the hash list must match comparing its registers.
+            let mut extended = registers.clone();
+            extended.insert(&element(size));
+            assert_eq!(
+                hash_list.may_contain_all(&extended),
+                registers.may_contain_all(&extended),
+                "Size: {size}."
+            );
+        }
+    }
+
+    #[test_estimator]
+    fn test_convert_hash_list_to_hyperloglog<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let mut converted = HyperLogLog::<P, B, R, H>::default();
+        let mut reference = HyperLogLog::<P, B, R, H>::default();
+        // The registers of an empty counter may not have grown to their full capacity yet.
+        reference.convert_hash_list_to_hyperloglog().unwrap();
+        assert!(!reference.is_hash_list());
+
+        for position in 0..100 {
+            let element = splitmix64(splitmix64(position));
+            converted.insert(&element);
+            reference.insert(&element);
+        }
+
+        if converted.is_hash_list() {
+            converted.convert_hash_list_to_hyperloglog().unwrap();
+        }
+        assert!(!converted.is_hash_list());
+        assert!(converted.convert_hash_list_to_hyperloglog().is_err());
+        assert!(converted
+            .registers
+            .iter_registers()
+            .eq(reference.registers.iter_registers()));
+        assert!((converted.harmonic_sum - reference.harmonic_sum).abs() < 1e-6);
+    }
+
+    #[test_estimator]
+    fn test_merge<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        // The largest hash lists overflow once merged, while the counters holding one more
+        // element than the capacity are in registers mode.
+        let sizes = [0, 1, capacity, capacity + 1];
+
+        for left_size in sizes {
+            for right_size in sizes {
+                // The right counter shares half of the elements of the left counter.
+                let right_start = left_size / 2;
+                let right_end = right_start + right_size;
+
+                let mut left = HyperLogLog::<P, B, R, H>::default();
+                let mut right = HyperLogLog::<P, B, R, H>::default();
+                for position in 0..left_size {
+                    left.insert(&element(position));
+                }
+                for position in right_start..right_end {
+                    right.insert(&element(position));
+                }
+
+                let merged = &left | &right;
+
+                for position in 0..left_size.max(right_end) {
+                    assert!(
+                        merged.may_contain(&element(position)),
+                        "The merged counter must contain the element at position {position}. Left size: {left_size}, right size: {right_size}."
+                    );
+                }
+
+                assert!(left.is_hash_list() && right.is_hash_list() || !merged.is_hash_list());
+
+                // The counters of the largest precisions weigh on the stack, so each of the
+                // following checks only keeps its own counter alive.
+                {
+                    let symmetric = &right | &left;
+                    assert_eq!(merged.is_hash_list(), symmetric.is_hash_list());
+                    if merged.is_hash_list() {
+                        assert_eq!(
+                            merged.uncorrected_estimate_cardinality().to_bits(),
+                            symmetric.uncorrected_estimate_cardinality().to_bits()
+                        );
+                    } else {
+                        assert!(merged
+                            .registers
+                            .iter_registers()
+                            .eq(symmetric.registers.iter_registers()));
+                        assert!((merged.harmonic_sum - symmetric.harmonic_sum).abs() < 1e-6);
+                    }
+                }
+
+                if !merged.is_hash_list() {
+                    let mut reference = HyperLogLog::<P, B, R, H>::default();
+                    for position in 0..left_size.max(right_end) {
+                        reference.insert(&element(position));
+                    }
+                    if !reference.is_hash_list() {
+                        assert!(merged
+                            .registers
+                            .iter_registers()
+                            .eq(reference.registers.iter_registers()));
+                    }
+                }
+
+                left |= right;
+                assert_eq!(left.is_hash_list(), merged.is_hash_list());
+                assert_eq!(
+                    left.uncorrected_estimate_cardinality().to_bits(),
+                    merged.uncorrected_estimate_cardinality().to_bits()
+                );
+            }
+        }
+    }
+
+    #[test_estimator]
+    fn test_insert_many<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let batch_size = u64::try_from(MINIMAL_INSERT_BATCH_SIZE).unwrap();
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+
+        // The sizes around the first batch and around the switch to the registers, which
+        // happens in the middle of the batches of the largest sizes.
+        for size in [
+            0,
+            1,
+            batch_size,
+            batch_size + 1,
+            capacity,
+            capacity + 1,
+            2 * capacity,
+        ] {
+            let elements: Vec<u64> = (0..size).map(element).collect();
+
+            let mut batched = HyperLogLog::<P, B, R, H>::default();
+            assert_eq!(batched.insert_many(&elements), size > 0);
+            assert!(!batched.insert_many(&elements));
+
+            let collected: HyperLogLog<P, B, R, H> = elements.iter().collect();
+
+            let mut extended = HyperLogLog::<P, B, R, H>::default();
+            extended.extend(elements.iter().copied());
+
+            // The duplicated elements fall in the same batches as the originals.
+            let duplicated: Vec<u64> = elements
+                .iter()
+                .flat_map(|&element| [element, element])
+                .collect();
+            let mut deduplicated = HyperLogLog::<P, B, R, H>::default();
+            assert_eq!(deduplicated.insert_many(&duplicated), size > 0);
+
+            let mut reference = HyperLogLog::<P, B, R, H>::default();
+            reference.convert_hash_list_to_hyperloglog().unwrap();
+            reference.insert_many(&elements);
+
+            for counter in [&batched, &collected, &extended] {
+                assert_eq!(counter.is_hash_list(), batched.is_hash_list());
+                assert_eq!(
+                    counter.uncorrected_estimate_cardinality().to_bits(),
+                    batched.uncorrected_estimate_cardinality().to_bits()
+                );
+            }
+
+            for counter in [&batched, &collected, &extended, &deduplicated] {
+                for element in &elements {
+                    assert!(
+                        counter.may_contain(element),
+                        "The counter must contain the element {element}. Size: {size}."
+                    );
+                }
+                if !counter.is_hash_list() {
+                    assert!(counter
+                        .registers
+                        .iter_registers()
+                        .eq(reference.registers.iter_registers()));
+                }
+            }
+        }
+    }
+
+    #[test_estimator]
+    fn test_insert_hash<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let mut hashes = [0_u64; 2_000];
+        let mut by_element = HyperLogLog::<P, B, R, H>::default();
+        let mut by_hash = HyperLogLog::<P, B, R, H>::default();
+
+        for (position, hash) in hashes.iter_mut().enumerate() {
+            let element = splitmix64(position as u64);
+            *hash = by_element.hasher().hash_one(element);
+
+            assert_eq!(by_element.insert(&element), by_hash.insert_hash(*hash));
+            assert!(by_hash.may_contain(&element));
+            assert!(by_element.may_contain_hash(*hash));
+            assert_eq!(
+                by_element.harmonic_sum.to_bits(),
+                by_hash.harmonic_sum.to_bits()
+            );
+            assert_eq!(
+                AsRef::<[u8]>::as_ref(&by_element.registers),
+                AsRef::<[u8]>::as_ref(&by_hash.registers)
+            );
+        }
+
+        let mut batched = HyperLogLog::<P, B, R, H>::default();
+        assert!(batched.insert_many_hashes(&hashes));
+        assert!(!batched.insert_many_hashes(&hashes));
+        assert!(batched.may_contain_all_hashes(&hashes));
+        assert_eq!(batched.is_hash_list(), by_hash.is_hash_list());
+    }
+
+    #[test_estimator]
+    fn test_estimate_with_confidence<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let confidence = 0.99_f64;
+        let mut checks = 0;
+        let mut misses = 0;
+        let mut check = |estimate: Estimate, exact: f64, point: f64| {
+            assert_eq!(estimate.value.to_bits(), point.to_bits());
+            assert_eq!(estimate.confidence.to_bits(), confidence.to_bits());
+            assert!(estimate.lower <= estimate.value && estimate.value <= estimate.upper);
+            checks += 1;
+            misses += usize::from(!estimate.contains(exact));
+        };
+
+        let capacity =
+            u32::try_from(HyperLogLog::<P, B, R, H>::hash_list_capacity(splitmix64)).unwrap();
+
+        // The exact intervals of the hash lists, the linear counting of the registers right after
+        // the switch, and the registers holding a few times as many elements as the hash list.
+        for size in [0, 1, capacity, capacity + 1, 4 * capacity] {
+            // The right counter shares half of the elements of the left counter.
+            let mut left = HyperLogLog::<P, B, R, H>::default();
+            let mut right = HyperLogLog::<P, B, R, H>::default();
+            left.extend((0..u64::from(size)).map(splitmix64));
+            right.extend((u64::from(size / 2)..u64::from(size / 2 + size)).map(splitmix64));
+
+            let exact_cardinality = f64::from(size);
+            let exact_intersection = f64::from(size - size / 2);
+            let exact_union = f64::from(size + size / 2);
+
+            check(
+                left.estimate_cardinality_with_confidence(confidence),
+                exact_cardinality,
+                left.estimate_cardinality(),
+            );
+            check(
+                left.estimate_union_cardinality_with_confidence(&right, confidence),
+                exact_union,
+                left.estimate_union_cardinality(&right),
+            );
+            check(
+                left.estimate_intersection_cardinality_with_confidence(&right, confidence),
+                exact_intersection,
+                left.estimate_intersection_cardinality(&right),
+            );
+            check(
+                left.estimate_difference_cardinality_with_confidence(&right, confidence),
+                f64::from(size / 2),
+                left.estimate_difference_cardinality(&right),
+            );
+            check(
+                left.estimate_jaccard_index_with_confidence(&right, confidence),
+                if size == 0 {
+                    0.0
+                } else {
+                    exact_intersection / exact_union
+                },
+                left.estimate_jaccard_index(&right),
+            );
+
+            if left.is_hash_list() {
+                let estimate = left.estimate_cardinality_with_confidence(confidence);
+                assert!(estimate.lower <= exact_cardinality);
+                assert!(estimate.contains(exact_cardinality));
+            }
+        }
+
+        assert!(
+            misses * 10 <= checks,
+            "The intervals missed the exact values {misses} times out of {checks}."
+        );
+    }
+
+    #[test_estimator]
+    fn test_estimate_containment<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let confidence = 0.95_f64;
+        let mut checks = 0;
+        let mut misses = 0;
+        let big_size = (1_u64 << P::EXPONENT).max(100);
+        let mut big = HyperLogLog::<P, B, R, H>::default();
+        big.extend((0..big_size).map(splitmix64));
+
+        let empty = HyperLogLog::<P, B, R, H>::default();
+        let estimate = empty.estimate_containment(&big, confidence);
+        assert_eq!((estimate.lower, estimate.upper), (0.0, 1.0));
+
+        for convert in [false, true] {
+            if convert && big.is_hash_list() {
+                big.convert_hash_list_to_hyperloglog().unwrap();
+            }
+            for shared in [0_u32, 25, 50, 75, 100] {
+                // The first shared elements of the small counter are in the big counter.
+                let mut small = HyperLogLog::<P, B, R, H>::default();
+                small.extend((0..100).map(|element| {
+                    splitmix64(if element < u64::from(shared) {
+                        element
+                    } else {
+                        big_size + element
+                    })
+                }));
+
+                let estimate = small.estimate_containment(&big, confidence);
+                assert_eq!(estimate.confidence.to_bits(), confidence.to_bits());
+                assert!(estimate.lower <= estimate.value && estimate.value <= estimate.upper);
+                assert!(estimate.lower >= 0.0 && estimate.upper <= 1.0);
+                checks += 1;
+                misses += usize::from(!estimate.contains(f64::from(shared) / 100.0));
+            }
+        }
+
+        assert!(
+            misses * 5 <= checks,
+            "The intervals missed the exact values {misses} times out of {checks}."
+        );
+    }
+
+    /// Checks that folding the provided counter to the provided precision preserves its elements.
+    fn check_fold<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        P2: Precision,
+        R2: Registers<P2, B>,
+    >(
+        counter: &HyperLogLog<P, B, R>,
+        elements: &[u64],
+    ) {
+        let folded = counter.fold_to::<P2, R2>().unwrap();
+        let mut reference = HyperLogLog::<P2, B, R2>::default();
+        reference.insert_many(elements);
+
+        for element in elements {
+            assert!(
+                folded.may_contain(element),
+                "The folded counter must contain the element {element}. Size: {}.",
+                elements.len()
+            );
+        }
+
+        assert!(counter.is_hash_list() || !folded.is_hash_list());
+
+        if folded.is_hash_list() {
+            assert_eq!(
+                folded.uncorrected_estimate_cardinality().to_bits(),
+                counter.uncorrected_estimate_cardinality().to_bits()
+            );
+        }
+
+        if !folded.is_hash_list() && !reference.is_hash_list() {
+            assert!(folded
+                .registers
+                .iter_registers()
+                .eq(reference.registers.iter_registers()));
+            assert!((folded.harmonic_sum - reference.harmonic_sum).abs() < 1e-6);
+        }
+    }
+
+    #[test_precisions_and_bits]
+    fn test_fold<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+        Precision4: PackedRegister<B>,
+    {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity =
+            HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::hash_list_capacity(element);
+        let folded_capacity =
+            HyperLogLog::<Precision4, B, <Precision4 as PackedRegister<B>>::Array>::hash_list_capacity(
+                element,
+            );
+
+        // The hash lists that still fit at the lowest precision, those that overflow it,
+        // and the counters already in registers mode.
+        for size in [0, 1, folded_capacity + 1, capacity, capacity + 1] {
+            let elements: Vec<u64> = (0..size).map(element).collect();
+            let mut counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
+            counter.insert_many(&elements);
+
+            check_fold::<P, B, _, P, <P as PackedRegister<B>>::Array>(&counter, &elements);
+            check_fold::<P, B, _, Precision4, <Precision4 as PackedRegister<B>>::Array>(
+                &counter, &elements,
+            );
+            #[cfg(feature = "alloc")]
+            check_fold::<P, B, _, Precision4, <Precision4 as PackedRegister<B>>::Vec>(
+                &counter, &elements,
+            );
+        }
+
+        if P::EXPONENT > 4 {
+            let counter =
+                HyperLogLog::<Precision4, B, <Precision4 as PackedRegister<B>>::Array>::default();
+            assert!(counter
+                .fold_to::<P, <P as PackedRegister<B>>::Array>()
+                .is_err());
+        }
+    }
+
+    /// Checks that converting the provided counter to the provided bits preserves its hashes.
+    fn check_convert_bits<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        B2: Bits,
+        R2: Registers<P, B2>,
+    >(
+        counter: &HyperLogLog<P, B, R>,
+        hashes: &[u64],
+    ) {
+        let converted = counter.convert_bits::<B2, R2>().unwrap();
+        let mut reference = HyperLogLog::<P, B2, R2>::default();
+        reference.insert_many_hashes(hashes);
+
+        for &hash in hashes {
+            let (_, register, _) = HyperLogLog::<P, B, R>::index_and_register_from_hash(hash);
+            // Widening cannot recover the registers that were clamped.
+            if B2::NUMBER_OF_BITS > B::NUMBER_OF_BITS && u64::from(register) == B::MASK {
+                continue;
+            }
+            assert!(
+                converted.may_contain_hash(hash),
+                "The converted counter must contain the hash {hash}."
+            );
+        }
+
+        assert!(counter.is_hash_list() || !converted.is_hash_list());
+
+        if converted.is_hash_list() {
+            assert_eq!(
+                converted.uncorrected_estimate_cardinality().to_bits(),
+                counter.uncorrected_estimate_cardinality().to_bits()
+            );
+        } else {
+            assert!(converted.harmonic_sum.is_finite());
+        }
+
+        if !counter.is_hash_list() {
+            assert!(converted.registers.iter_registers().eq(counter
+                .registers
+                .iter_registers()
+                .map(|register| register.min(u8::try_from(B2::MASK).unwrap()))));
+        }
+
+        if B2::NUMBER_OF_BITS <= B::NUMBER_OF_BITS
+            && !converted.is_hash_list()
+            && !reference.is_hash_list()
+        {
+            assert!(converted
+                .registers
+                .iter_registers()
+                .eq(reference.registers.iter_registers()));
+            assert!((converted.harmonic_sum - reference.harmonic_sum).abs() < 1e-6);
+        }
+    }
+
+    #[test_precisions_and_bits]
+    fn test_convert_bits<P, B: Bits>()
+    where
+        P: PackedRegister<B>
+            + PackedRegister<Bits4>
+            + PackedRegister<Bits5>
+            + PackedRegister<Bits6>,
+    {
+        // The hashes of the registers right below, at and right above the largest
+        // values of four and five bits, each at its own index.
+        let hashes: Vec<u64> = [1_u64, 14, 15, 16, 30, 31, 32, 40]
+            .into_iter()
+            .zip(0_u64..)
+            .map(|(register, index)| 1 << (64 - register) | index)
+            .collect();
+
+        for convert in [false, true] {
+            let mut counter = HyperLogLog::<P, B, <P as PackedRegister<B>>::Array>::default();
+            counter.insert_many_hashes(&hashes);
+            if convert {
+                counter.convert_hash_list_to_hyperloglog().unwrap();
+            }
+
+            check_convert_bits::<P, B, _, Bits4, <P as PackedRegister<Bits4>>::Array>(
+                &counter, &hashes,
+            );
+            check_convert_bits::<P, B, _, Bits5, <P as PackedRegister<Bits5>>::Array>(
+                &counter, &hashes,
+            );
+            check_convert_bits::<P, B, _, Bits6, <P as PackedRegister<Bits6>>::Array>(
+                &counter, &hashes,
+            );
+            #[cfg(feature = "alloc")]
+            check_convert_bits::<P, B, _, Bits4, <P as PackedRegister<Bits4>>::Vec>(
+                &counter, &hashes,
+            );
+        }
+    }
+
+    #[test_estimator]
+    fn test_improved_estimate<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let mut cardinality_errors = 0.0;
+        let mut union_errors = 0.0;
+        let mut samples = 0.0;
+        let mut random_state = splitmix64(P::EXPONENT.into());
+
+        for size in [10_u32, 100, 1_000, 10_000] {
+            for _ in 0..5 {
+                random_state = splitmix64(random_state);
+                let mut left = HyperLogLog::<P, B, R, H>::default();
+                let mut right = HyperLogLog::<P, B, R, H>::default();
+                // The two sets share half of their elements.
+                let elements: Vec<u64> = (0..size + size / 2)
+                    .map(|element| random_state ^ u64::from(element))
+                    .collect();
+                left.insert_many(&elements[..size as usize]);
+                right.insert_many(&elements[size as usize / 2..]);
+
+                // In hash list mode, the estimate is the number of hashes and duplicates.
+                if left.is_hash_list() {
+                    assert_eq!(
+                        left.improved_estimate_cardinality().to_bits(),
+                        left.uncorrected_estimate_cardinality().to_bits()
+                    );
+                    let mut union = left.clone();
+                    union.merge(&right);
+                    assert_eq!(
+                        left.improved_estimate_union_cardinality(&right).to_bits(),
+                        union.improved_estimate_cardinality().to_bits()
+                    );
+                }
+
+                for counter in [&mut left, &mut right] {
+                    if counter.is_hash_list() {
+                        counter.convert_hash_list_to_hyperloglog().unwrap();
+                    }
+                }
+
+                let exact = f64::from(size);
+                let exact_union = f64::from(size + size / 2);
+                cardinality_errors += (left.improved_estimate_cardinality() - exact).abs() / exact;
+                union_errors += (left.improved_estimate_union_cardinality(&right) - exact_union)
+                    .abs()
+                    / exact_union;
+                samples += 1.0;
+            }
+        }
+
+        assert!(
+            cardinality_errors / samples <= P::error_rate(),
+            "Cardinality error rate ({}) is higher than expected ({}).",
+            cardinality_errors / samples,
+            P::error_rate()
+        );
+        assert!(
+            union_errors / samples <= P::error_rate(),
+            "Union error rate ({}) is higher than expected ({}).",
+            union_errors / samples,
+            P::error_rate()
+        );
+
+        let empty = HyperLogLog::<P, B, R, H>::default();
+        let mut registers = empty.clone();
+        registers.convert_hash_list_to_hyperloglog().unwrap();
+        assert_eq!(
+            registers.improved_estimate_cardinality().to_bits(),
+            0.0_f64.to_bits()
+        );
+        assert_eq!(
+            registers
+                .improved_estimate_union_cardinality(&empty)
+                .to_bits(),
+            0.0_f64.to_bits()
+        );
+    }
+
+    #[test]
+    fn test_sigma_and_tau() {
+        assert_eq!(sigma(0.0).to_bits(), 0.0_f64.to_bits());
+        assert!(sigma(1.0).is_infinite());
+        // sigma(1/2) = 1/2 + sum_k 2^(-2^k) 2^(k-1)
+        assert!((sigma(0.5) - 0.5 - 0.25 - 0.125 - 0.015_625 - 0.000_122_070_312_5).abs() < 1e-6);
+        assert_eq!(tau(0.0).to_bits(), 0.0_f64.to_bits());
+        assert_eq!(tau(1.0).to_bits(), 0.0_f64.to_bits());
+        assert!(tau(0.5) > 0.0 && tau(0.5) < 0.5 / 3.0);
+    }
+
+    #[derive(Clone)]
+    /// Hasher builder seeding the hashers with the provided seed.
+    struct SeededXxHash64(u64);
+
+    impl BuildHasher for SeededXxHash64 {
+        type Hasher = twox_hash::XxHash64;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            twox_hash::XxHash64::with_seed(self.0)
+        }
+    }
+
+    /// Checks that the counters hash their elements with the seed of their hasher builder.
+    fn check_seeded_hasher<P: Precision, B: Bits, R: Registers<P, B>>() {
+        let mut first: HyperLogLog<P, B, R, SeededXxHash64> =
+            HyperLogLog::with_hasher(SeededXxHash64(1));
+        let mut same_seed: HyperLogLog<P, B, R, SeededXxHash64> =
+            HyperLogLog::with_hasher(SeededXxHash64(1));
+        let mut other_seed: HyperLogLog<P, B, R, SeededXxHash64> =
+            HyperLogLog::with_hasher(SeededXxHash64(2));
+        let mut unseeded =
+            HyperLogLog::<P, B, R, BuildHasherDefault<twox_hash::XxHash64>>::default();
+
+        for position in 0..2_000_u64 {
+            let element = splitmix64(position);
+            first.insert(&element);
+            same_seed.insert(&element);
+            other_seed.insert(&element);
+            unseeded.insert(&element);
+            assert!(first.may_contain(&element));
+            assert!(other_seed.may_contain(&element));
+        }
+
+        assert_eq!(
+            AsRef::<[u8]>::as_ref(&first.registers),
+            AsRef::<[u8]>::as_ref(&same_seed.registers)
+        );
+        assert_ne!(
+            AsRef::<[u8]>::as_ref(&first.registers),
+            AsRef::<[u8]>::as_ref(&other_seed.registers)
+        );
+
+        // Seed zero is the seed used by the default hasher.
+        let mut zero_seed: HyperLogLog<P, B, R, SeededXxHash64> =
+            HyperLogLog::with_hasher(SeededXxHash64(0));
+        for position in 0..2_000_u64 {
+            zero_seed.insert(&splitmix64(position));
+        }
+        assert_eq!(
+            AsRef::<[u8]>::as_ref(&zero_seed.registers),
+            AsRef::<[u8]>::as_ref(&unseeded.registers)
+        );
+    }
+
+    #[test]
+    fn test_seeded_hasher() {
+        check_seeded_hasher::<Precision4, Bits4, <Precision4 as PackedRegister<Bits4>>::Array>();
+        check_seeded_hasher::<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Vec>();
+        check_seeded_hasher::<Precision12, Bits5, <Precision12 as PackedRegister<Bits5>>::Array>();
+        check_seeded_hasher::<Precision18, Bits6, <Precision18 as PackedRegister<Bits6>>::Vec>();
+    }
+
+    #[test]
+    #[should_panic(expected = "The counters hash their elements with different hasher builders.")]
+    fn test_merge_with_different_hasher_builders() {
+        let mut first: HyperLogLog<
+            Precision8,
+            Bits6,
+            <Precision8 as PackedRegister<Bits6>>::Array,
+            SeededXxHash64,
+        > = HyperLogLog::with_hasher(SeededXxHash64(1));
+        let other_seed = HyperLogLog::with_hasher(SeededXxHash64(2));
+
+        first |= &other_seed;
+    }
+
+    #[test_estimator]
+    fn test_try_insert_and_try_merge<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let same = |left: &HyperLogLog<P, B, R, SeededXxHash64>,
+                    right: &HyperLogLog<P, B, R, SeededXxHash64>| {
+            left.harmonic_sum.to_bits() == right.harmonic_sum.to_bits()
+                && AsRef::<[u8]>::as_ref(&left.registers) == AsRef::<[u8]>::as_ref(&right.registers)
+        };
+
+        for size in [10_u64, 1_000, 10_000] {
+            let mut left: HyperLogLog<P, B, R, SeededXxHash64> =
+                HyperLogLog::with_hasher(SeededXxHash64(1));
+            let mut right = left.clone();
+            let mut inserted = left.clone();
+
+            for position in 0..size {
+                let element = splitmix64(position);
+                assert_eq!(left.try_insert(&element), Ok(inserted.insert(&element)));
+                right.try_insert(&splitmix64(position + size / 2)).unwrap();
+            }
+            assert!(same(&left, &inserted));
+
+            let mut merged = left.clone();
+            merged.merge(&right);
+            left.try_merge(&right).unwrap();
+            assert!(same(&left, &merged));
+
+            // Counters built with different hashers are not merged.
+            let other_seed: HyperLogLog<P, B, R, SeededXxHash64> =
+                HyperLogLog::with_hasher(SeededXxHash64(2));
+            assert!(matches!(
+                left.try_merge(&other_seed),
+                Err(Error::IncompatibleConfiguration(_))
+            ));
+            assert!(same(&left, &merged));
+        }
+
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        assert_eq!(
+            hll.number_of_zero_registers(),
+            Err(Error::ModeMismatch {
+                expected: Mode::Registers
+            })
+        );
+        hll.convert_hash_list_to_hyperloglog().unwrap();
+        assert_eq!(hll.number_of_zero_registers(), Ok(1 << P::EXPONENT));
+        assert_eq!(
+            hll.convert_hash_list_to_hyperloglog(),
+            Err(Error::ModeMismatch {
+                expected: Mode::HashList
+            })
+        );
+        assert_eq!(hll.mode(), Mode::Registers);
+    }
 }
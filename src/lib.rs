@@ -10,16 +10,27 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod bits;
+mod bytes;
 pub mod composite_hash;
-pub mod hyperloglog;
-// #[cfg(feature = "mle")]
-// mod mle;
 mod correction_coefficients;
+#[cfg(feature = "alloc")]
+mod dynamic;
+mod error;
+mod estimate;
+#[cfg(feature = "alloc")]
+mod graph;
 mod hash_list;
+mod hip;
+pub mod hyperloglog;
+#[cfg(feature = "mle")]
+mod mle;
 mod precisions;
 mod registers;
 pub mod sketches;
+mod stats;
 pub mod utils;
+#[cfg(feature = "alloc")]
+mod venn;
 
 #[cfg(feature = "serde")]
 pub mod serde;
@@ -27,12 +38,23 @@ pub mod serde;
 /// Re-exports of the most important traits and structs.
 pub mod prelude {
     pub use crate::bits::*;
+    pub use crate::bytes::{SERIALIZATION_FORMAT_VERSION, SERIALIZATION_HEADER_SIZE};
+    #[cfg(feature = "alloc")]
+    pub use crate::dynamic::*;
+    pub use crate::error::{Error, Mode};
+    pub use crate::estimate::Estimate;
+    #[cfg(feature = "alloc")]
+    pub use crate::graph::*;
+    pub use crate::hip::*;
     pub use crate::hyperloglog::*;
-    // #[cfg(feature = "mle")]
-    // pub use crate::mle::*;
+    #[cfg(feature = "mle")]
+    pub use crate::mle::*;
     pub use crate::precisions::*;
     pub use crate::registers::*;
     pub use crate::sketches::*;
+    pub use crate::stats::*;
     pub use crate::utils::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::venn::*;
     pub use core::ops::{BitOr, BitOrAssign};
 }
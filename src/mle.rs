@@ -1,30 +1,108 @@
 //! Struct marker MLE.
 
-use crate::basicloglog::BasicLogLog;
+use crate::hyperloglog::maximal_register_value;
 use crate::prelude::*;
 use core::cmp::Ordering;
 use core::hash::Hash;
 use core::ops::{Add, Mul, Sub};
 
-#[derive(Debug, Clone, Copy, Hash, Default, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 /// A struct representing the Maximum Likelihood Estimation.
+///
+/// # Implementative details
+/// The wrapped counter is used as is to insert and merge elements, while the estimates
+/// of counters in registers mode are replaced by their maximum likelihood estimates.
+/// Counters in hash list mode are already almost exact, and are estimated as usual.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut hll: MLE<HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array>> =
+///     Default::default();
+///
+/// hll.insert(&42);
+/// hll.insert(&43);
+///
+/// assert!(hll.as_ref().may_contain(&42));
+/// assert!(hll.estimate_cardinality() > 1.9 && hll.estimate_cardinality() < 2.1);
+/// ```
 pub struct MLE<H, const ERROR: i32 = 2> {
     /// The underlying counter.
     counter: H,
 }
 
-impl<X, H: AsMut<X>, const ERROR: i32> AsMut<X> for MLE<H, ERROR> {
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// The cardinalities of the regions of two sets, estimated jointly.
+pub struct JointCardinalities {
+    /// The cardinality of the elements only in the left set.
+    pub left_difference: f64,
+    /// The cardinality of the elements only in the right set.
+    pub right_difference: f64,
+    /// The cardinality of the elements in both sets.
+    pub intersection: f64,
+}
+
+impl JointCardinalities {
+    #[inline]
+    #[must_use]
+    /// Returns the cardinality of the left set.
+    pub fn left(&self) -> f64 {
+        self.left_difference + self.intersection
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the cardinality of the right set.
+    pub fn right(&self) -> f64 {
+        self.right_difference + self.intersection
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the cardinality of the union of the two sets.
+    pub fn union(&self) -> f64 {
+        self.left_difference + self.right_difference + self.intersection
+    }
+
     #[inline]
-    fn as_mut(&mut self) -> &mut X {
-        self.counter.as_mut()
+    #[must_use]
+    /// Returns the cardinalities with the roles of the left and right sets exchanged.
+    pub fn swapped(&self) -> Self {
+        Self {
+            left_difference: self.right_difference,
+            right_difference: self.left_difference,
+            intersection: self.intersection,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the Jaccard index of the two sets.
+    pub fn jaccard_index(&self) -> f64 {
+        let union = self.union();
+        if union.is_zero() {
+            0.0
+        } else {
+            self.intersection / union
+        }
+    }
+}
+
+impl<H, const ERROR: i32> AsMut<H> for MLE<H, ERROR> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut H {
+        &mut self.counter
     }
 }
 
-impl<X, H: AsRef<X>, const ERROR: i32> AsRef<X> for MLE<H, ERROR> {
+impl<H, const ERROR: i32> AsRef<H> for MLE<H, ERROR> {
     #[inline]
-    fn as_ref(&self) -> &X {
-        self.counter.as_ref()
+    fn as_ref(&self) -> &H {
+        &self.counter
     }
 }
 
@@ -46,63 +124,256 @@ impl<H: BitOrAssign, const ERROR: i32> BitOrAssign for MLE<H, ERROR> {
     }
 }
 
-impl<
-        H: HyperLogLog + AsMut<BasicLogLog<H::Precision, H::Bits, H::Registers, H::Hasher>>,
-        const ERROR: i32,
-    > HyperLogLog for MLE<H, ERROR>
-{
-    type Registers = H::Registers;
-    type Precision = H::Precision;
-    type Bits = H::Bits;
-    type Hasher = H::Hasher;
-
+impl<H, const ERROR: i32> From<H> for MLE<H, ERROR> {
     #[inline]
-    fn registers(&self) -> &Self::Registers {
-        self.counter.registers()
+    fn from(counter: H) -> Self {
+        Self { counter }
     }
+}
 
+impl<T: Hash, H: Extend<T>, const ERROR: i32> Extend<T> for MLE<H, ERROR> {
     #[inline]
-    fn get_number_of_zero_registers(&self) -> u32 {
-        self.counter.get_number_of_zero_registers()
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.counter.extend(iter);
     }
+}
 
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType, const ERROR: i32>
+    MLE<HyperLogLog<P, B, R, H>, ERROR>
+{
     #[inline]
-    fn get_register(&self, index: usize) -> u8 {
-        self.counter.get_register(index)
+    /// Inserts the element into the counter, returning whether the counter changed.
+    pub fn insert<T: Hash>(&mut self, element: &T) -> bool {
+        self.counter.insert(element)
     }
 
     #[inline]
-    fn harmonic_sum(&self) -> f64 {
-        self.counter.harmonic_sum()
+    /// Merges the provided counter into the current one.
+    pub fn merge(&mut self, other: &Self) {
+        self.counter.merge(&other.counter);
     }
 
     #[inline]
-    fn insert_register_value_and_index(&mut self, new_register_value: u8, index: usize) -> bool {
+    /// Returns the counter wrapped by the estimator.
+    pub fn into_inner(self) -> HyperLogLog<P, B, R, H> {
         self.counter
-            .insert_register_value_and_index(new_register_value, index)
     }
 
     #[inline]
-    fn from_registers(registers: H::Registers) -> Self {
-        Self {
-            counter: HyperLogLog::from_registers(registers),
+    /// Returns the maximum likelihood estimate of the cardinality.
+    pub fn estimate_cardinality(&self) -> f64 {
+        if self.counter.is_hash_list() {
+            return self.counter.estimate_cardinality();
         }
+        mle_cardinality::<P, B, ERROR>(&self.counter.register_histogram())
+            .unwrap_or_else(|| self.counter.estimate_cardinality())
     }
-}
 
-impl<H, const ERROR: i32> From<H> for MLE<H, ERROR> {
     #[inline]
-    fn from(counter: H) -> Self {
-        Self { counter }
+    /// Returns the joint maximum likelihood estimate of the union cardinality of two counters.
+    pub fn estimate_union_cardinality(&self, other: &Self) -> f64 {
+        if self.counter.is_hash_list() || other.counter.is_hash_list() {
+            return self.counter.estimate_union_cardinality_with_cardinalities(
+                &other.counter,
+                self.estimate_cardinality(),
+                other.estimate_cardinality(),
+            );
+        }
+        self.estimate_joint_cardinalities(other).union()
+    }
+
+    #[inline]
+    /// Returns the joint maximum likelihood estimate of the cardinalities of the
+    /// differences and of the intersection of two counters.
+    ///
+    /// # Panics
+    /// If a counter in hash list mode cannot be converted to registers, as its storage
+    /// cannot be extended to hold all of them.
+    ///
+    /// # Implementative details
+    /// Estimating the intersection by inclusion-exclusion of three separately estimated
+    /// cardinalities leads to large relative errors when the intersection is small compared
+    /// to the sets. The three regions are instead estimated at once from the number of
+    /// registers, for each register value, where the left register is larger, smaller or
+    /// equal to the right register. When both counters are hash lists, the shared hashes
+    /// are counted directly, and when only one of them is, it is first converted to registers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut left: MLE<HyperLogLog<Precision12, Bits6, <Precision12 as PackedRegister<Bits6>>::Array>> =
+    ///     Default::default();
+    /// let mut right: MLE<HyperLogLog<Precision12, Bits6, <Precision12 as PackedRegister<Bits6>>::Array>> =
+    ///     Default::default();
+    ///
+    /// left.extend(0..50_000_u64);
+    /// right.extend(45_000..100_000_u64);
+    ///
+    /// let joint = left.estimate_joint_cardinalities(&right);
+    ///
+    /// assert!(joint.intersection > 4_000.0 && joint.intersection < 6_000.0, "{joint:?}");
+    /// assert!(joint.left_difference > 40_000.0 && joint.left_difference < 50_000.0, "{joint:?}");
+    /// assert!(joint.right_difference > 45_000.0 && joint.right_difference < 55_000.0, "{joint:?}");
+    /// ```
+    pub fn estimate_joint_cardinalities(&self, other: &Self) -> JointCardinalities {
+        match (self.counter.is_hash_list(), other.counter.is_hash_list()) {
+            (true, true) => {
+                let self_cardinality = self.estimate_cardinality();
+                let other_cardinality = other.estimate_cardinality();
+                let intersection =
+                    f64::from(self.counter.hash_lists_intersection_size(&other.counter))
+                        .min(self_cardinality)
+                        .min(other_cardinality);
+                JointCardinalities {
+                    left_difference: self_cardinality - intersection,
+                    right_difference: other_cardinality - intersection,
+                    intersection,
+                }
+            }
+            (true, false) => {
+                let mut converted = self.clone();
+                converted
+                    .counter
+                    .convert_hash_list_to_hyperloglog()
+                    .unwrap();
+                converted.estimate_joint_cardinalities(other)
+            }
+            (false, true) => {
+                let mut converted = other.clone();
+                converted
+                    .counter
+                    .convert_hash_list_to_hyperloglog()
+                    .unwrap();
+                self.estimate_joint_cardinalities(&converted)
+            }
+            (false, false) => {
+                let self_cardinality = self.estimate_cardinality();
+                let other_cardinality = other.estimate_cardinality();
+                let union_cardinality = self.counter.estimate_union_cardinality_with_cardinalities(
+                    &other.counter,
+                    self_cardinality,
+                    other_cardinality,
+                );
+                mle_joint_cardinalities::<P, B, R::IterZipped<'_>, ERROR>(
+                    self.counter
+                        .registers
+                        .iter_registers_zipped(&other.counter.registers),
+                    self_cardinality,
+                    other_cardinality,
+                    union_cardinality,
+                )
+            }
+        }
+    }
+
+    #[inline]
+    /// Returns the joint maximum likelihood estimate of the intersection cardinality between two counters.
+    pub fn estimate_intersection_cardinality(&self, other: &Self) -> f64 {
+        self.estimate_joint_cardinalities(other).intersection
+    }
+
+    #[inline]
+    /// Returns the joint maximum likelihood estimate of the Jaccard index between two counters.
+    pub fn estimate_jaccard_index(&self, other: &Self) -> f64 {
+        self.estimate_joint_cardinalities(other).jaccard_index()
+    }
+
+    #[inline]
+    /// Returns the joint maximum likelihood estimate of the cardinality of the elements
+    /// of the current counter that are not in the other counter.
+    pub fn estimate_difference_cardinality(&self, other: &Self) -> f64 {
+        self.estimate_joint_cardinalities(other).left_difference
     }
 }
 
+/// Compute the cardinality using the Maximum Likelihood Estimation.
+///
+/// # Arguments
+/// * `histogram` - The number of registers of the counter holding each value.
+///
+/// # Implementative details
+/// Under the Poissonization assumption, the registers are independent and hold a value
+/// not larger than k with probability exp(-x 2^(-k)), where x is the expected number of
+/// elements per register. The maximum of the likelihood is the only root of its derivative,
+/// which decreases monotonically with x and is therefore found by bisection of log(x).
+/// Returns `None` when all registers are saturated, as the likelihood has no maximum.
+fn mle_cardinality<P: Precision, B: Bits, const ERROR: i32>(histogram: &[u32; 64]) -> Option<f64> {
+    let q_plus_one = maximal_register_value::<P, B>();
+    let q = q_plus_one - 1;
+    let number_of_registers = f64::integer_exp2(P::EXPONENT);
+    let multiplicities = &histogram[..=usize::from(q_plus_one)];
+
+    if multiplicities[0] == 1 << P::EXPONENT {
+        return Some(f64::ZERO);
+    }
+
+    if multiplicities[usize::from(q_plus_one)] == 1 << P::EXPONENT {
+        return None;
+    }
+
+    // The derivative of the log-likelihood, multiplied by x, is the difference between
+    // sum_k C_k g(x 2^(-k)) with g(y) = y / (exp(y) - 1), and x sum_k C_k 2^(-k),
+    // where saturated registers contribute with g(x 2^(-q)) and no term to the latter sum.
+    let linear_term: f64 = multiplicities[..=usize::from(q)]
+        .iter()
+        .enumerate()
+        .map(|(register, multiplicity)| {
+            f64::from(*multiplicity) * f64::integer_exp2_minus(u8::try_from(register).unwrap())
+        })
+        .sum();
+
+    let derivative = |log_x: f64| -> f64 {
+        let x = log_x.exp();
+        let g = |y: f64| -> f64 {
+            if y.is_zero() {
+                1.0
+            } else {
+                y / y.exp_m1()
+            }
+        };
+        let nonlinear_term: f64 = (1..=q)
+            .map(|register| {
+                f64::from(multiplicities[usize::from(register)])
+                    * g(x * f64::integer_exp2_minus(register))
+            })
+            .sum::<f64>()
+            + f64::from(multiplicities[usize::from(q_plus_one)])
+                * g(x * f64::integer_exp2_minus(q));
+        nonlinear_term - x * linear_term
+    };
+
+    // The root lies between the estimate of a single element, which is at least a
+    // fraction of an element per register, and the estimate of saturated registers.
+    let mut lower = -f64::from(P::EXPONENT + P::EXPONENT) * core::f64::consts::LN_2;
+    let mut upper = f64::from(q_plus_one + P::EXPONENT) * core::f64::consts::LN_2;
+    let tolerance = 10.0_f64.powi(-ERROR) / number_of_registers.sqrt();
+
+    while upper - lower > tolerance {
+        let middle = f64::midpoint(lower, upper);
+        if derivative(middle) > 0.0 {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    Some(number_of_registers * f64::midpoint(lower, upper).exp())
+}
+
 #[expect(
     clippy::too_many_lines,
     reason = "I don't want to split the function in smaller parts."
 )]
-/// Compute the union cardinality using the Maximum Likelihood Estimation.
-fn mle_union_cardinality<
+/// Compute the cardinalities of the differences and of the intersection using the Maximum Likelihood Estimation.
+///
+/// # Arguments
+/// * `registers` - The pairs of registers of the two counters.
+/// * `left_cardinality` - The estimated cardinality of the left counter.
+/// * `right_cardinality` - The estimated cardinality of the right counter.
+/// * `union_cardinality` - The estimated union cardinality, used as the starting point of the optimization.
+fn mle_joint_cardinalities<
     P: Precision,
     B: Bits,
     I: ExactSizeIterator<Item = [u8; 2]>,
@@ -111,14 +382,13 @@ fn mle_union_cardinality<
     registers: I,
     left_cardinality: f64,
     right_cardinality: f64,
-    estimate: fn(f64, u32) -> f64,
-) -> f64 {
+    union_cardinality: f64,
+) -> JointCardinalities {
     let mut left_multiplicities_larger = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
     let mut left_multiplicities_smaller = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
     let mut right_multiplicities_larger = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
     let mut right_multiplicities_smaller = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
     let mut joint_multiplicities = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut union_harmonic_sum = f64::ZERO;
     let mut union_zeros = 0;
 
     for [left_register, right_register] in registers {
@@ -137,19 +407,14 @@ fn mle_union_cardinality<
         right_multiplicities_smaller[right_register] += f64::from(cmp == Ordering::Greater);
         joint_multiplicities[left_register] += f64::from(cmp == Ordering::Equal);
 
-        union_harmonic_sum += f64::integer_exp2_minus(larger_register);
         union_zeros += u32::from(larger_register.is_zero());
     }
 
-    // We get the best estimates from HyperLogLog++
-    let union_cardinality = estimate(union_harmonic_sum, union_zeros);
-
-    // If the sum of the number of registers equal to zero, i.e.
-    // the first value in the multiplicities vectors, is equal
-    // to the number of registers, it means that the intersection
-    // is empty.
-    if union_zeros == 1 << B::NUMBER_OF_BITS {
-        return f64::ZERO;
+    // If the number of registers equal to zero in the union
+    // is equal to the number of registers, it means that the
+    // union is empty.
+    if union_zeros == 1 << P::EXPONENT {
+        return JointCardinalities::default();
     }
 
     let intersection: f64 =
@@ -185,7 +450,7 @@ fn mle_union_cardinality<
     };
 
     // We precompute q and q+1 for reference.
-    let q_plus_one: u8 = (1 << B::NUMBER_OF_BITS) - 1;
+    let q_plus_one: u8 = maximal_register_value::<P, B>();
     let q: u8 = q_plus_one - 1;
 
     // We initialize the vectors for the Adam optimizer.
@@ -283,7 +548,11 @@ fn mle_union_cardinality<
         }
     }
 
-    phis[0].exp() + phis[1].exp() + phis[2].exp()
+    JointCardinalities {
+        left_difference: phis[0].exp(),
+        right_difference: phis[1].exp(),
+        intersection: phis[2].exp(),
+    }
 }
 
 /// Trait for element-wise multiplication.
@@ -350,52 +619,6 @@ impl<const N: usize, T: Default + Copy + Add<T, Output = T>> ElementWiseAddition
     }
 }
 
-impl<const ERROR: i32, H: Correction> Correction for MLE<H, ERROR> {
-    fn correction(harmonic_sum: f64, number_of_zero_registers: u32) -> f64 {
-        H::correction(harmonic_sum, number_of_zero_registers)
-    }
-}
-
-impl<const ERROR: i32, H> Estimator<f64> for MLE<H, ERROR>
-where
-    H: Estimator<f64> + Correction + HyperLogLog,
-{
-    #[inline]
-    fn estimate_cardinality(&self) -> f64 {
-        self.counter.estimate_cardinality()
-    }
-
-    #[inline]
-    fn is_union_estimate_non_deterministic(&self, _other: &Self) -> bool {
-        true
-    }
-
-    #[inline]
-    fn estimate_union_cardinality_with_cardinalities(
-        &self,
-        other: &Self,
-        self_cardinality: f64,
-        other_cardinality: f64,
-    ) -> f64 {
-        mle_union_cardinality::<
-            <H as HyperLogLog>::Precision,
-            <H as HyperLogLog>::Bits,
-            <<H as HyperLogLog>::Registers as Registers<
-                <H as HyperLogLog>::Precision,
-                <H as HyperLogLog>::Bits,
-            >>::IterZipped<'_>,
-            ERROR,
-        >(
-            self.counter
-                .registers()
-                .iter_registers_zipped(other.counter.registers()),
-            self_cardinality,
-            other_cardinality,
-            <H as Correction>::correction,
-        )
-    }
-}
-
 /// Adam optimizer for the Maximum Likelihood Estimation.
 struct Adam<const N: usize> {
     /// First moments.
@@ -457,6 +680,183 @@ impl<const N: usize> Adam<N> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    #[test_precisions_and_bits]
+    fn test_mle_estimates<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut cardinality_errors = 0.0;
+        let mut union_errors = 0.0;
+        let mut intersection_errors = 0.0;
+        let mut samples = 0.0;
+        let mut random_state = splitmix64(P::EXPONENT.into());
+
+        for size in [10_u32, 100, 1_000, 10_000] {
+            for _ in 0..5 {
+                random_state = splitmix64(random_state);
+                let mut left: MLE<HyperLogLog<P, B, <P as PackedRegister<B>>::Array>> =
+                    MLE::default();
+                let mut right: MLE<HyperLogLog<P, B, <P as PackedRegister<B>>::Array>> =
+                    MLE::default();
+                // The two sets share half of their elements.
+                for element in 0..size {
+                    left.insert(&(random_state ^ u64::from(element)));
+                    right.insert(&(random_state ^ u64::from(element + size / 2)));
+                }
+
+                // Counters in hash list mode are estimated as usual.
+                if left.as_ref().is_hash_list() && right.as_ref().is_hash_list() {
+                    assert_eq!(
+                        left.estimate_cardinality().to_bits(),
+                        left.as_ref().estimate_cardinality().to_bits()
+                    );
+                    assert_eq!(
+                        left.estimate_union_cardinality(&right).to_bits(),
+                        left.as_ref()
+                            .estimate_union_cardinality(right.as_ref())
+                            .to_bits()
+                    );
+                }
+
+                for counter in [&mut left, &mut right] {
+                    if counter.as_ref().is_hash_list() {
+                        counter.as_mut().convert_hash_list_to_hyperloglog().unwrap();
+                    }
+                }
+
+                let exact = f64::from(size);
+                let exact_union = f64::from(size + size / 2);
+                let exact_intersection = f64::from(size - size / 2);
+
+                cardinality_errors += (left.estimate_cardinality() - exact).abs() / exact;
+                union_errors +=
+                    (left.estimate_union_cardinality(&right) - exact_union).abs() / exact_union;
+                intersection_errors +=
+                    (left.estimate_intersection_cardinality(&right) - exact_intersection).abs()
+                        / exact_intersection;
+                samples += 1.0;
+            }
+        }
+
+        assert!(
+            cardinality_errors / samples <= P::error_rate(),
+            "Cardinality error rate ({}) is higher than expected ({}).",
+            cardinality_errors / samples,
+            P::error_rate()
+        );
+        assert!(
+            union_errors / samples <= P::error_rate(),
+            "Union error rate ({}) is higher than expected ({}).",
+            union_errors / samples,
+            P::error_rate()
+        );
+        // The intersection is smaller than the sets, hence its relative error is larger.
+        assert!(
+            intersection_errors / samples <= 2.0 * P::error_rate(),
+            "Intersection error rate ({}) is higher than expected ({}).",
+            intersection_errors / samples,
+            2.0 * P::error_rate()
+        );
+    }
+
+    #[test_precisions_and_bits]
+    fn test_joint_cardinalities<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut intersection_errors = 0.0;
+        let mut samples = 0.0;
+        let mut random_state = splitmix64(P::EXPONENT.into());
+
+        for size in [10_u32, 100, 1_000, 10_000] {
+            for _ in 0..5 {
+                random_state = splitmix64(random_state);
+                // The two sets share a quarter of their elements.
+                let elements: Vec<u64> = (0..2 * size - size / 4)
+                    .map(|element| random_state ^ u64::from(element))
+                    .collect();
+                let mut left: MLE<HyperLogLog<P, B, <P as PackedRegister<B>>::Array>> =
+                    MLE::default();
+                let mut right: MLE<HyperLogLog<P, B, <P as PackedRegister<B>>::Array>> =
+                    MLE::default();
+                left.as_mut().insert_many(&elements[..size as usize]);
+                right
+                    .as_mut()
+                    .insert_many(&elements[(size - size / 4) as usize..]);
+                let exact_intersection = f64::from(size / 4);
+
+                if left.as_ref().is_hash_list() && right.as_ref().is_hash_list() {
+                    let joint = left.estimate_joint_cardinalities(&right);
+                    assert!(joint.intersection <= left.estimate_cardinality());
+                    assert!(joint.intersection <= right.estimate_cardinality());
+                    assert_eq!(
+                        joint.left().to_bits(),
+                        left.estimate_cardinality().to_bits()
+                    );
+                }
+
+                if right.as_ref().is_hash_list() {
+                    right.as_mut().convert_hash_list_to_hyperloglog().unwrap();
+                }
+
+                // Pairs of a hash list and registers are estimated as pairs of registers.
+                let mixed = left.estimate_joint_cardinalities(&right);
+
+                if left.as_ref().is_hash_list() {
+                    left.as_mut().convert_hash_list_to_hyperloglog().unwrap();
+                }
+
+                let joint = left.estimate_joint_cardinalities(&right);
+                assert_eq!(mixed, joint);
+                assert_eq!(
+                    joint.union().to_bits(),
+                    left.estimate_union_cardinality(&right).to_bits()
+                );
+                assert_eq!(
+                    joint.left_difference.to_bits(),
+                    left.estimate_difference_cardinality(&right).to_bits()
+                );
+                assert_eq!(
+                    joint.intersection.to_bits(),
+                    left.estimate_intersection_cardinality(&right).to_bits()
+                );
+                intersection_errors +=
+                    (joint.intersection - exact_intersection).abs() / exact_intersection;
+                samples += 1.0;
+            }
+        }
+
+        // The intersection is a quarter of the sets, hence its relative error is larger.
+        assert!(
+            intersection_errors / samples <= 5.0 * P::error_rate(),
+            "Intersection error rate ({}) is higher than expected ({}).",
+            intersection_errors / samples,
+            5.0 * P::error_rate()
+        );
+    }
+
+    #[test]
+    fn test_mle_empty_and_saturated() {
+        let mut hll: MLE<
+            HyperLogLog<Precision4, Bits4, <Precision4 as PackedRegister<Bits4>>::Array>,
+        > = MLE::default();
+        assert!(hll.estimate_cardinality().abs() < f64::EPSILON);
+        assert!(hll.estimate_union_cardinality(&hll).abs() < f64::EPSILON);
+
+        // Saturated registers have no maximum likelihood estimate, and fall back to the counter.
+        hll.as_mut().convert_hash_list_to_hyperloglog().unwrap();
+        // Hashes whose bits other than the index are all zeros reach the largest register value.
+        for index in 0..16 {
+            hll.as_mut().insert_hash(index);
+        }
+        assert!(hll.as_ref().is_full());
+        assert_eq!(
+            hll.estimate_cardinality().to_bits(),
+            hll.as_ref().estimate_cardinality().to_bits()
+        );
+    }
 
     // Test function: f(x) = -(x1 - 1)^2 - (x2 + 2)^2
     fn quadratic_function(phis: &[f64; 2]) -> (f64, [f64; 2]) {
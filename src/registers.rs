@@ -5,8 +5,10 @@ use core::hash::Hash;
 
 use crate::prelude::*;
 use crate::utils::{FloatOps, Zero};
+mod histogram;
 mod packed_array;
 
+pub use histogram::HistogramRegisters;
 pub use packed_array::{Packed, PackedRegister};
 
 /// Trait marker for the registers.
@@ -24,11 +26,30 @@ pub trait Registers<P: Precision, B: Bits>:
         Self: 'registers;
 
     /// Doubles or saturates the size of the registers.
-    fn increase_capacity(&mut self);
+    ///
+    /// # Errors
+    /// * If the registers already have their maximal size, or cannot be resized.
+    fn increase_capacity(&mut self) -> Result<(), Error>;
+
+    /// Returns the number of bytes used by the registers, including the ones allocated on the heap.
+    fn memory_footprint(&self) -> usize;
 
     /// Returns an iterator over the registers.
     fn iter_registers(&self) -> Self::Iter<'_>;
 
+    /// Returns the number of registers holding each register value, indexed by the value.
+    ///
+    /// # Implementative details
+    /// The default implementation scans all of the registers, while registers keeping track
+    /// of their histogram, such as [`HistogramRegisters`], return it in constant time.
+    fn register_histogram(&self) -> [u32; 64] {
+        let mut histogram = [0_u32; 64];
+        for register in self.iter_registers() {
+            histogram[usize::from(register)] += 1;
+        }
+        histogram
+    }
+
     /// Returns a random register.
     fn random(&self, random_state: u64) -> (usize, u8) {
         let index = xorshift64(random_state) as usize % (1 << P::EXPONENT);
@@ -148,4 +169,24 @@ pub trait Registers<P: Precision, B: Bits>:
 
     /// Returns the struct bitsize.
     fn bitsize() -> usize;
+
+    /// Writes the registers into the provided bytes using their canonical, endian-independent layout.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes where the registers are written, at most as many as the bytes of the registers.
+    ///
+    /// # Implementative details
+    /// In the canonical layout, the register `i` occupies the bits from `i * B` to `(i + 1) * B`
+    /// of the stream of bits starting from the most significant bit of the first byte. When fewer
+    /// bytes than the bytes of the registers are provided, only the first bytes of the layout are written.
+    fn write_canonical_bytes(&self, bytes: &mut [u8]);
+
+    /// Reads the registers from the provided bytes using their canonical, endian-independent layout.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes from which the registers are read, at most as many as the bytes of the registers.
+    ///
+    /// # Implementative details
+    /// When fewer bytes than the bytes of the registers are provided, the remaining bits are set to zero.
+    fn read_canonical_bytes(&mut self, bytes: &[u8]);
 }
@@ -0,0 +1,354 @@
+//! Submodule providing registers that keep the histogram of their values up to date.
+//!
+//! Several estimators, such as linear counting, the improved raw estimator and the maximum
+//! likelihood estimator, only depend on the number of registers holding each value. Wrapping
+//! the registers in [`HistogramRegisters`] makes the histogram available in constant time,
+//! at the cost of updating it whenever a register changes.
+
+use super::{Bits, Error, Precision, Registers};
+use core::hash::{Hash, Hasher};
+use core::mem::size_of;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::{MemDbg, MemSize};
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "mem_dbg", derive(MemDbg, MemSize))]
+/// Registers wrapper keeping the number of registers holding each value up to date.
+///
+/// # Implementative details
+/// The bin of the zero value is stored as the wrapping offset from the number of registers,
+/// so that the wrapper does not need to know the precision when created. As the histogram is
+/// derived from the registers, equality and hashing only consider the registers. The histogram is
+/// only meaningful in registers mode: while the counter is a hash list, the wrapped storage
+/// holds the encoded hashes and the histogram is left untouched.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut hll: HyperLogLog<
+///     Precision12,
+///     Bits6,
+///     HistogramRegisters<<Precision12 as PackedRegister<Bits6>>::Array>,
+/// > = Default::default();
+/// hll.extend(0..10_000_u64);
+///
+/// let estimate = hll.improved_estimate_cardinality();
+/// assert!(estimate > 9_500.0 && estimate < 10_500.0, "{estimate}");
+/// ```
+pub struct HistogramRegisters<R> {
+    /// The wrapped registers.
+    registers: R,
+    /// The number of registers holding each value, with the zero bin offset by the number of registers.
+    histogram: [u32; 64],
+}
+
+impl<R: Default> Default for HistogramRegisters<R> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            registers: R::default(),
+            histogram: [0; 64],
+        }
+    }
+}
+
+impl<R: PartialEq> PartialEq for HistogramRegisters<R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.registers == other.registers
+    }
+}
+
+impl<R: Eq> Eq for HistogramRegisters<R> {}
+
+impl<R: Hash> Hash for HistogramRegisters<R> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.registers.hash(state);
+    }
+}
+
+impl<R> HistogramRegisters<R> {
+    #[inline]
+    #[must_use]
+    /// Wraps the provided registers, computing the histogram of their values.
+    ///
+    /// # Arguments
+    /// * `registers` - The registers to wrap, which must not hold an encoded hash list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut registers = <Precision8 as PackedRegister<Bits6>>::Array::default();
+    /// Registers::<Precision8, Bits6>::set(&mut registers, 3, 5);
+    ///
+    /// let wrapped = HistogramRegisters::new::<Precision8, Bits6>(registers);
+    /// let histogram = Registers::<Precision8, Bits6>::register_histogram(&wrapped);
+    ///
+    /// assert_eq!(histogram[0], 255);
+    /// assert_eq!(histogram[5], 1);
+    /// ```
+    pub fn new<P: Precision, B: Bits>(registers: R) -> Self
+    where
+        R: Registers<P, B>,
+    {
+        let mut wrapped = Self {
+            registers,
+            histogram: [0; 64],
+        };
+        wrapped.recompute();
+        wrapped
+    }
+
+    #[inline]
+    /// Returns the wrapped registers.
+    pub fn into_inner(self) -> R {
+        self.registers
+    }
+
+    #[inline]
+    /// Moves a register from the bin of its previous value to the bin of its new value.
+    fn record(&mut self, old_value: u8, new_value: u8) {
+        if old_value != new_value {
+            let old_bin = &mut self.histogram[usize::from(old_value)];
+            *old_bin = old_bin.wrapping_sub(1);
+            let new_bin = &mut self.histogram[usize::from(new_value)];
+            *new_bin = new_bin.wrapping_add(1);
+        }
+    }
+
+    #[inline]
+    /// Recomputes the histogram by scanning the wrapped registers.
+    fn recompute<P: Precision, B: Bits>(&mut self)
+    where
+        R: Registers<P, B>,
+    {
+        self.histogram = self.registers.register_histogram();
+        self.histogram[0] = self.histogram[0].wrapping_sub(1 << P::EXPONENT);
+    }
+}
+
+impl<R: AsRef<[u8]>> AsRef<[u8]> for HistogramRegisters<R> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.registers.as_ref()
+    }
+}
+
+impl<R: AsMut<[u8]>> AsMut<[u8]> for HistogramRegisters<R> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.registers.as_mut()
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>> Registers<P, B> for HistogramRegisters<R> {
+    type Iter<'register>
+        = R::Iter<'register>
+    where
+        Self: 'register;
+    type IterZipped<'registers>
+        = R::IterZipped<'registers>
+    where
+        Self: 'registers;
+
+    #[inline]
+    fn increase_capacity(&mut self) -> Result<(), Error> {
+        self.registers.increase_capacity()
+    }
+
+    #[inline]
+    fn memory_footprint(&self) -> usize {
+        size_of::<Self>() - size_of::<R>() + self.registers.memory_footprint()
+    }
+
+    #[inline]
+    fn iter_registers(&self) -> Self::Iter<'_> {
+        self.registers.iter_registers()
+    }
+
+    #[inline]
+    fn iter_registers_zipped<'registers>(
+        &'registers self,
+        other: &'registers Self,
+    ) -> Self::IterZipped<'registers> {
+        self.registers.iter_registers_zipped(&other.registers)
+    }
+
+    #[inline]
+    fn register_histogram(&self) -> [u32; 64] {
+        let mut histogram = self.histogram;
+        histogram[0] = histogram[0].wrapping_add(1 << P::EXPONENT);
+        histogram
+    }
+
+    #[inline]
+    fn apply_to_registers<F>(&mut self, f: F)
+    where
+        F: FnMut(u8) -> u8,
+    {
+        self.registers.apply_to_registers(f);
+        self.recompute();
+    }
+
+    #[inline]
+    fn set_greater(&mut self, index: usize, value: u8) -> (u8, u8) {
+        let (old_value, new_value) = self.registers.set_greater(index, value);
+        self.record(old_value, new_value);
+        (old_value, new_value)
+    }
+
+    #[inline]
+    fn set(&mut self, index: usize, value: u8) {
+        let old_value = self.registers.get_register(index);
+        self.registers.set(index, value);
+        self.record(old_value, value);
+    }
+
+    #[inline]
+    fn get_register(&self, index: usize) -> u8 {
+        self.registers.get_register(index)
+    }
+
+    #[inline]
+    fn clear_registers(&mut self) {
+        self.registers.clear_registers();
+        self.histogram = [0; 64];
+    }
+
+    #[inline]
+    fn bitsize() -> usize {
+        R::bitsize()
+    }
+
+    #[inline]
+    fn write_canonical_bytes(&self, bytes: &mut [u8]) {
+        self.registers.write_canonical_bytes(bytes);
+    }
+
+    #[inline]
+    fn read_canonical_bytes(&mut self, bytes: &[u8]) {
+        self.registers.read_canonical_bytes(bytes);
+        self.recompute();
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+/// Test module for the registers keeping their histogram.
+mod test_histogram_registers {
+    use super::*;
+    use crate::prelude::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    type Plain<P, B> = HyperLogLog<P, B, <P as PackedRegister<B>>::Vec>;
+    type Tracked<P, B> = HyperLogLog<P, B, HistogramRegisters<<P as PackedRegister<B>>::Vec>>;
+
+    /// Returns the histogram of the registers, computed by scanning them.
+    fn scanned_histogram<P: Precision, B: Bits, R: Registers<P, B>>(registers: &R) -> [u32; 64] {
+        let mut histogram = [0_u32; 64];
+        for register in registers.iter_registers() {
+            histogram[usize::from(register)] += 1;
+        }
+        histogram
+    }
+
+    /// Asserts that the tracked counter describes the same registers as the plain one.
+    fn assert_consistent<P, B: Bits>(plain: &Plain<P, B>, tracked: &Tracked<P, B>)
+    where
+        P: PackedRegister<B>,
+    {
+        assert_eq!(plain.is_hash_list(), tracked.is_hash_list());
+        assert_eq!(plain.register_histogram(), tracked.register_histogram());
+        assert_eq!(
+            plain.improved_estimate_cardinality().to_bits(),
+            tracked.improved_estimate_cardinality().to_bits()
+        );
+        if !tracked.is_hash_list() {
+            assert_eq!(
+                tracked.registers.register_histogram(),
+                scanned_histogram(&tracked.registers)
+            );
+            assert_eq!(
+                tracked.number_of_zero_registers(),
+                plain.number_of_zero_registers()
+            );
+        }
+    }
+
+    #[test_precisions_and_bits]
+    fn test_histogram_registers<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut plain: Plain<P, B> = HyperLogLog::default();
+        let mut tracked: Tracked<P, B> = HyperLogLog::default();
+        assert_eq!(tracked.registers.register_histogram()[0], 1 << P::EXPONENT);
+
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = Plain::<P, B>::hash_list_capacity(element);
+        let mut inserted = 0;
+        // The histogram is computed when the hash list switches to the registers,
+        // and then updated with the registers.
+        for size in [1, capacity, capacity + 1, 2 * capacity] {
+            let elements = (inserted..size).map(element);
+            plain.extend(elements.clone());
+            tracked.extend(elements);
+            inserted = size;
+            assert_consistent(&plain, &tracked);
+        }
+
+        // Merging keeps the histogram up to date, whatever the modes of the counters.
+        for size in [1, capacity + 1] {
+            let elements = (0..size).map(|position| splitmix64(position ^ size));
+            let mut plain_other: Plain<P, B> = HyperLogLog::default();
+            let mut tracked_other: Tracked<P, B> = HyperLogLog::default();
+            plain_other.extend(elements.clone());
+            tracked_other.extend(elements);
+            plain.merge(&plain_other);
+            tracked.merge(&tracked_other);
+            assert_consistent(&plain, &tracked);
+        }
+
+        // Restoring a counter from its bytes recomputes the histogram.
+        let mut bytes = vec![0; tracked.serialized_size()];
+        tracked.to_bytes(&mut bytes).unwrap();
+        let restored: Tracked<P, B> = HyperLogLog::from_bytes(&bytes).unwrap();
+        assert_consistent(&plain, &restored);
+
+        // Wrapping registers that are not all zeros computes their histogram.
+        let mut plain_registers = <P as PackedRegister<B>>::Array::default();
+        Registers::<P, B>::set(&mut plain_registers, 1, 3);
+        let wrapped = HistogramRegisters::new::<P, B>(plain_registers);
+        assert_eq!(
+            Registers::<P, B>::register_histogram(&wrapped),
+            scanned_histogram(&wrapped)
+        );
+        let mut tracked_registers: HistogramRegisters<<P as PackedRegister<B>>::Array> =
+            HistogramRegisters::default();
+        Registers::<P, B>::set(&mut tracked_registers, 1, 3);
+        assert_eq!(tracked_registers, wrapped);
+
+        let mut registers =
+            HistogramRegisters::new::<P, B>(<P as PackedRegister<B>>::Array::default());
+        assert_eq!(registers, HistogramRegisters::default());
+        registers.set(0, 1);
+        registers.set(0, 2);
+        registers.set_greater(1, 1);
+        registers.set_greater(1, 0);
+        assert_eq!(
+            Registers::<P, B>::register_histogram(&registers),
+            scanned_histogram(&registers)
+        );
+        registers.clear_registers();
+        assert_eq!(
+            Registers::<P, B>::register_histogram(&registers)[0],
+            1 << P::EXPONENT
+        );
+    }
+}
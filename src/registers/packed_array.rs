@@ -10,7 +10,7 @@
 //! packed array, expecially in the case of bridge registers, i.e. registers that span two words.
 
 use super::Registers;
-use super::{Bits, Bits4, Bits5, Bits6, Matrix, Precision, Zero};
+use super::{Bits, Bits4, Bits5, Bits6, Error, Matrix, Precision, Zero};
 use super::{
     Precision10, Precision11, Precision12, Precision13, Precision14, Precision15, Precision16,
     Precision17, Precision18, Precision4, Precision5, Precision6, Precision7, Precision8,
@@ -500,6 +500,89 @@ impl<V: VariableWord> Default for Packed<Vec<u64>, V> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<W: AsRef<[u64]>, V> serde::Serialize for Packed<W, V> {
+    #[inline]
+    /// Serializes the words as big-endian bytes, so that the registers are portable across platforms.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes: Vec<u8> = self
+            .words
+            .as_ref()
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect();
+        crate::serde::serialize_bytes(&bytes, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Deserializes the big-endian bytes of the words of packed registers.
+///
+/// # Errors
+/// * If the number of bytes is not a multiple of the size of a word.
+fn deserialize_words<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u64>, D::Error> {
+    let bytes = crate::serde::deserialize_bytes(deserializer)?;
+    if !bytes.len().is_multiple_of(size_of::<u64>()) {
+        return Err(serde::de::Error::invalid_length(
+            bytes.len(),
+            &"a multiple of 8 bytes",
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(size_of::<u64>())
+        .map(|chunk| {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            u64::from_be_bytes(word)
+        })
+        .collect())
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize, V: VariableWord> serde::Deserialize<'de> for Packed<[u64; N], V> {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let words = deserialize_words(deserializer)?;
+        if words.len() != N {
+            return Err(serde::de::Error::invalid_length(
+                words.len() * size_of::<u64>(),
+                &"the number of bytes of the packed registers",
+            ));
+        }
+        let mut registers = Self::default();
+        registers.words.copy_from_slice(&words);
+        Ok(registers)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V: VariableWord> serde::Deserialize<'de> for Packed<Vec<u64>, V> {
+    #[inline]
+    /// Deserializes the words of the registers, which must be as many as the words of the
+    /// registers of one of the supported precisions, or none for empty registers.
+    ///
+    /// # Implementative details
+    /// Since the vector registers do not encode their precision, registers deserialized from
+    /// the words of registers of another precision with the same number of words are accepted.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let words = deserialize_words(deserializer)?;
+        let mut number_of_words = (Precision4::EXPONENT..=Precision18::EXPONENT)
+            .map(|exponent| ((1_usize << exponent) * V::NUMBER_OF_BITS_USIZE).div_ceil(64));
+        if !words.is_empty() && !number_of_words.any(|expected| expected == words.len()) {
+            return Err(serde::de::Error::invalid_length(
+                words.len() * size_of::<u64>(),
+                &"the number of bytes of the packed registers of a supported precision",
+            ));
+        }
+        Ok(Self {
+            words,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 /// Trait marker to associate a specific register array with a combination of precision and bits.
 ///
 /// Meant to be associated with a specific Precision.
@@ -534,22 +617,39 @@ const fn split_packed_index<V: VariableWord>(index: usize) -> (usize, u8) {
 }
 
 pub trait IncreaseCapacity {
-    fn increase_capacity(&mut self, maximal_size: usize);
+    fn increase_capacity(&mut self, maximal_size: usize) -> Result<(), Error>;
+
+    /// Returns the number of bytes allocated on the heap.
+    fn allocated_bytes(&self) -> usize;
 }
 
 impl<const N: usize> IncreaseCapacity for [u64; N] {
     #[inline]
-    fn increase_capacity(&mut self, _maximal_size: usize) {
-        unimplemented!("The increase_capacity method is not implemented for [u64; N]");
+    fn increase_capacity(&mut self, _maximal_size: usize) -> Result<(), Error> {
+        Err(Error::CapacityExhausted)
+    }
+
+    #[inline]
+    fn allocated_bytes(&self) -> usize {
+        0
     }
 }
 
 #[cfg(feature = "alloc")]
 impl IncreaseCapacity for Vec<u64> {
     #[inline]
-    fn increase_capacity(&mut self, maximal_size: usize) {
+    fn increase_capacity(&mut self, maximal_size: usize) -> Result<(), Error> {
+        if self.len() >= maximal_size {
+            return Err(Error::CapacityExhausted);
+        }
         let new_length = if self.is_empty() { 1 } else { self.len() * 2 }.min(maximal_size);
         self.resize(new_length, 0);
+        Ok(())
+    }
+
+    #[inline]
+    fn allocated_bytes(&self) -> usize {
+        self.capacity() * size_of::<u64>()
     }
 }
 
@@ -568,9 +668,14 @@ where
             Self: 'words;
 
     #[inline]
-    fn increase_capacity(&mut self) {
+    fn increase_capacity(&mut self) -> Result<(), Error> {
         self.words
-            .increase_capacity(((1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE).div_ceil(64));
+            .increase_capacity(((1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE).div_ceil(64))
+    }
+
+    #[inline]
+    fn memory_footprint(&self) -> usize {
+        size_of::<Self>() + self.words.allocated_bytes()
     }
 
     #[inline]
@@ -666,6 +771,39 @@ where
     fn bitsize() -> usize {
         64 * ((1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE).div_ceil(64)
     }
+
+    #[inline]
+    fn write_canonical_bytes(&self, bytes: &mut [u8]) {
+        let words: &[u64] = self.words.as_ref();
+        assert!(
+            bytes.len() <= words.len() * 8,
+            "The number of bytes ({}) must not exceed the number of bytes of the registers ({}).",
+            bytes.len(),
+            words.len() * 8
+        );
+        // The registers are packed starting from the most significant bit of each word,
+        // hence storing the words in big-endian order results in the canonical layout.
+        for (chunk, word) in bytes.chunks_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes()[..chunk.len()]);
+        }
+    }
+
+    #[inline]
+    fn read_canonical_bytes(&mut self, bytes: &[u8]) {
+        self.clear();
+        let words: &mut [u64] = self.words.as_mut();
+        assert!(
+            bytes.len() <= words.len() * 8,
+            "The number of bytes ({}) must not exceed the number of bytes of the registers ({}).",
+            bytes.len(),
+            words.len() * 8
+        );
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks(8)) {
+            let mut word_bytes = [0; 8];
+            word_bytes[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_be_bytes(word_bytes);
+        }
+    }
 }
 
 /// Implement the packed array registers for a specific combination of precision and bits.
@@ -792,3 +930,89 @@ mod test_split_index {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+/// Test module for the canonical layout of the packed registers.
+mod test_canonical_bytes {
+    use super::*;
+    use crate::prelude::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    #[test_precisions_and_bits]
+    fn test_canonical_bytes<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut registers = <P as PackedRegister<B>>::Array::default();
+        let mut random_state =
+            splitmix64(u64::from(P::EXPONENT) << 8 | u64::from(B::NUMBER_OF_BITS));
+        for index in 0..1 << P::EXPONENT {
+            random_state = splitmix64(random_state);
+            registers.set(
+                index,
+                u8::try_from(random_state % (1 << B::NUMBER_OF_BITS)).unwrap(),
+            );
+        }
+
+        let mut bytes =
+            vec![0; <<P as PackedRegister<B>>::Array as Registers<P, B>>::bitsize() / 8];
+        registers.write_canonical_bytes(&mut bytes);
+
+        // The register in position i occupies the bits from i * B to (i + 1) * B, starting
+        // from the most significant bit of the first byte.
+        for (index, register) in registers.iter_registers().enumerate() {
+            let value = (0..usize::from(B::NUMBER_OF_BITS)).fold(0_u8, |value, offset| {
+                let bit_index = index * usize::from(B::NUMBER_OF_BITS) + offset;
+                (value << 1) | ((bytes[bit_index / 8] >> (7 - bit_index % 8)) & 1)
+            });
+            assert_eq!(value, register);
+        }
+
+        let mut restored = <P as PackedRegister<B>>::Array::default();
+        restored.read_canonical_bytes(&bytes);
+        assert_eq!(restored, registers);
+
+        // Reading a prefix of the canonical layout restores the corresponding registers.
+        let number_of_registers = (1 << P::EXPONENT) / 2;
+        let prefix_size = (number_of_registers * usize::from(B::NUMBER_OF_BITS)).div_ceil(8);
+        let mut partial = <P as PackedRegister<B>>::Array::default();
+        partial.read_canonical_bytes(&bytes[..prefix_size]);
+        let mut prefix = vec![0; prefix_size];
+        partial.write_canonical_bytes(&mut prefix);
+        assert_eq!(prefix, bytes[..prefix_size]);
+        assert!(partial
+            .iter_registers()
+            .zip(registers.iter_registers())
+            .take(number_of_registers)
+            .all(|(left, right)| left == right));
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+/// Test module for the capacity of the packed registers.
+mod test_increase_capacity {
+    use super::*;
+    use crate::prelude::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    #[test_precisions_and_bits]
+    fn test_increase_capacity<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut array = <P as PackedRegister<B>>::Array::default();
+        assert_eq!(array.increase_capacity(), Err(Error::CapacityExhausted));
+
+        let mut vector = <P as PackedRegister<B>>::Vec::default();
+        while AsRef::<[u8]>::as_ref(&vector).len() < AsRef::<[u8]>::as_ref(&array).len() {
+            vector.increase_capacity().unwrap();
+        }
+        assert_eq!(
+            AsRef::<[u8]>::as_ref(&vector).len(),
+            AsRef::<[u8]>::as_ref(&array).len()
+        );
+        assert_eq!(vector.increase_capacity(), Err(Error::CapacityExhausted));
+    }
+}
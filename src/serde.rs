@@ -1,51 +1,261 @@
-//! Module to handle serialization and deserialization of the registers
-use core::any::type_name;
-use core::fmt::Formatter;
-use core::marker::PhantomData;
-use serde::de::SeqAccess;
-use serde::de::Visitor;
-
-/// Struct to deserialize a vector of T
-pub(crate) struct RegisterVisitor<T> {
-    /// The expected length of the vector
-    expected_length: usize,
-    /// Phantom data to keep the type
-    _phantom: PhantomData<T>,
-}
-
-impl<T> RegisterVisitor<T> {
-    /// Creates a new [`RegisterVisitor`]
-    pub(crate) fn new(expected_length: usize) -> Self {
-        Self {
-            expected_length,
-            _phantom: PhantomData,
+//! Module to handle serialization and deserialization of the counters and of their registers.
+//!
+//! Counters are serialized using the versioned binary format of [`HyperLogLog::to_bytes`],
+//! which is validated upon deserialization. Binary formats store the bytes as they are, while
+//! human-readable formats store them as a hexadecimal string.
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use serde::de::{Error, SeqAccess, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Wrapper to display bytes as a lowercase hexadecimal string.
+struct Hex<'a>(&'a [u8]);
+
+impl Display for Hex<'_> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(formatter, "{byte:02x}")?;
         }
+        Ok(())
     }
 }
 
-impl<'de, T: Default + Copy + serde::Deserialize<'de>> Visitor<'de> for RegisterVisitor<T> {
-    type Value = Vec<T>;
+#[inline]
+/// Returns the value of the provided hexadecimal digit.
+fn hex_digit(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Visitor to deserialize bytes, either as a byte array or as a hexadecimal string.
+pub(crate) struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
 
     fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
-        formatter.write_str(&format!(
-            "an array of {} {} elements",
-            self.expected_length,
-            type_name::<T>()
-        ))
+        formatter.write_str("a byte array or a hexadecimal string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        if !value.len().is_multiple_of(2) {
+            return Err(E::invalid_length(value.len(), &self));
+        }
+        value
+            .as_bytes()
+            .chunks_exact(2)
+            .map(
+                |digits| match (hex_digit(digits[0]), hex_digit(digits[1])) {
+                    (Some(high), Some(low)) => Ok(high << 4 | low),
+                    _ => Err(E::invalid_value(Unexpected::Str(value), &self)),
+                },
+            )
+            .collect()
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(value)
     }
 
     fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
-        let mut array = vec![T::default(); self.expected_length];
-        {
-            let mut array_iter = array.iter_mut();
-            while let Some(value) = seq.next_element()? {
-                if let Some(target) = array_iter.next() {
-                    *target = value;
-                } else {
-                    return Err(serde::de::Error::invalid_length(array.len(), &self));
-                }
-            }
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[inline]
+/// Serializes the provided bytes, as a hexadecimal string in human-readable formats.
+pub(crate) fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+#[inline]
+/// Deserializes bytes, from a hexadecimal string in human-readable formats.
+pub(crate) fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> Serialize
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![0; self.serialized_size()];
+        self.to_bytes(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        serialize_bytes(&bytes, serializer)
+    }
+}
+
+impl<'de, P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default> Deserialize<'de>
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        Self::from_bytes(&bytes).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_estimator;
+    use twox_hash::XxHash;
+
+    #[test]
+    fn test_hex() {
+        let bytes = [0x00, 0x01, 0xAB, 0xFF];
+        assert_eq!(Hex(&bytes).to_string(), "0001abff");
+        assert_eq!(
+            BytesVisitor
+                .visit_str::<serde_json::Error>("0001abFF")
+                .unwrap(),
+            bytes
+        );
+        assert!(BytesVisitor.visit_str::<serde_json::Error>("000").is_err());
+        assert!(BytesVisitor.visit_str::<serde_json::Error>("0g").is_err());
+    }
+
+    #[test_estimator]
+    fn test_serde<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        let mut inserted = 0;
+
+        // The empty counter, a hash list and the registers.
+        for size in [0, 1, capacity + 1] {
+            hll.extend((inserted..size).map(element));
+            inserted = size;
+
+            let mut bytes = vec![0; hll.serialized_size()];
+            hll.to_bytes(&mut bytes).unwrap();
+
+            // Binary formats store the bytes of the counter as they are.
+            let binary = postcard::to_allocvec(&hll).unwrap();
+            assert!(binary.len() <= bytes.len() + 4);
+            let restored: HyperLogLog<P, B, R, H> = postcard::from_bytes(&binary).unwrap();
+            let mut restored_bytes = vec![0; restored.serialized_size()];
+            restored.to_bytes(&mut restored_bytes).unwrap();
+            assert_eq!(restored_bytes, bytes);
+
+            // Human-readable formats store them as a hexadecimal string.
+            let json = serde_json::to_string(&hll).unwrap();
+            assert_eq!(json, format!("\"{}\"", Hex(&bytes)));
+            let restored: HyperLogLog<P, B, R, H> = serde_json::from_str(&json).unwrap();
+            let mut restored_bytes = vec![0; restored.serialized_size()];
+            restored.to_bytes(&mut restored_bytes).unwrap();
+            assert_eq!(restored_bytes, bytes);
+            assert!((0..size).all(|position| restored.may_contain(&element(position))));
+
+            // Corrupted or truncated counters are rejected.
+            let mut corrupted = bytes.clone();
+            *corrupted.last_mut().unwrap() ^= 1;
+            assert!(serde_json::from_str::<HyperLogLog<P, B, R, H>>(&format!(
+                "\"{}\"",
+                Hex(&corrupted)
+            ))
+            .is_err());
+            assert!(
+                postcard::from_bytes::<HyperLogLog<P, B, R, H>>(&binary[..binary.len() - 1])
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_serde_rejects_other_configurations() {
+        let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+            HyperLogLog::default();
+        hll.extend(0..1_000);
+        let json = serde_json::to_string(&hll).unwrap();
+
+        assert!(serde_json::from_str::<
+            HyperLogLog<Precision9, Bits6, <Precision9 as PackedRegister<Bits6>>::Array>,
+        >(&json)
+        .is_err());
+        assert!(serde_json::from_str::<
+            HyperLogLog<Precision8, Bits5, <Precision8 as PackedRegister<Bits5>>::Array>,
+        >(&json)
+        .is_err());
+
+        // Counters are portable across register types of the same precision and bits.
+        let restored: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Vec> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.estimate_cardinality().to_bits(),
+            hll.estimate_cardinality().to_bits()
+        );
+    }
+
+    #[test]
+    fn test_serde_packed() {
+        let mut registers = <Precision6 as PackedRegister<Bits5>>::Array::default();
+        for index in 0..64 {
+            registers.set(index, u8::try_from(index % 32).unwrap());
+        }
+
+        let json = serde_json::to_string(&registers).unwrap();
+        let restored: <Precision6 as PackedRegister<Bits5>>::Array =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, registers);
+
+        let binary = postcard::to_allocvec(&registers).unwrap();
+        let restored: <Precision6 as PackedRegister<Bits5>>::Vec =
+            postcard::from_bytes(&binary).unwrap();
+        assert!(
+            Registers::<Precision6, Bits5>::iter_registers(&restored).eq(Registers::<
+                Precision6,
+                Bits5,
+            >::iter_registers(
+                &registers
+            ))
+        );
+
+        // The number of words of array registers must match.
+        assert!(
+            serde_json::from_str::<<Precision7 as PackedRegister<Bits5>>::Array>(&json).is_err()
+        );
+
+        // The number of words of vector registers must match the one of a supported precision.
+        for number_of_words in [1, 4, 6, 20_481] {
+            let json = format!("\"{}\"", Hex(&vec![0; number_of_words * 8]));
+            assert!(
+                serde_json::from_str::<<Precision6 as PackedRegister<Bits5>>::Vec>(&json).is_err(),
+                "The {number_of_words} words must be rejected."
+            );
+        }
+        for number_of_words in [0, 2, 3, 5, 10, 20_480] {
+            let json = format!("\"{}\"", Hex(&vec![0; number_of_words * 8]));
+            assert!(
+                serde_json::from_str::<<Precision6 as PackedRegister<Bits5>>::Vec>(&json).is_ok(),
+                "The {number_of_words} words must be accepted."
+            );
         }
-        Ok(array)
     }
 }
@@ -13,7 +13,17 @@
 //! sometimes it is necessary, such as in test cases, to have the exact version of the
 //! algorithm. The approximated version is faster and uses less memory, but it is not,
 //! of course, guaranteed to be exact.
-use crate::prelude::{FloatOps, Number, Zero};
+#[cfg(feature = "alloc")]
+use crate::prelude::{Error, RowMajorMatrix};
+use crate::prelude::{
+    Bits, FloatOps, HasherType, HyperLogLog, Number, Precision, Registers, Zero,
+};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
 
 /// Trait for sketching algorithms that provide the overlap and differences cardinality matrices.
 pub trait HyperSpheresSketch<N: Number>: Sized {
@@ -51,68 +61,82 @@ pub trait HyperSpheresSketch<N: Number>: Sized {
         lefts: &[Self; L],
         rights: &[Self; R],
     ) -> ([[N; R]; L], [N; L], [N; R]) {
-        // Initialize overlap and differences cardinality matrices/vectors.
-        let mut last_row = [N::ZERO; R];
         let mut differential_overlap_cardinality_matrix = [[N::ZERO; R]; L];
         let mut left_difference_cardinality_vector = [N::ZERO; L];
-        let mut right_cardinalities = [N::ZERO; R];
+        let mut right_difference_cardinality_vector = [N::ZERO; R];
 
-        rights
-            .iter()
-            .zip(right_cardinalities.iter_mut())
-            .for_each(|(right, right_cardinality)| {
-                *right_cardinality = right.estimate_cardinality();
-            });
+        differential_overlap_and_differences_cardinalities(
+            &lefts.each_ref().map(Self::estimate_cardinality),
+            &rights.each_ref().map(Self::estimate_cardinality),
+            &|i, j| lefts[i].estimate_union_cardinality(&rights[j]),
+            differential_overlap_cardinality_matrix.as_flattened_mut(),
+            &mut left_difference_cardinality_vector,
+            &mut right_difference_cardinality_vector,
+            &mut [N::ZERO; R],
+        );
 
-        let mut right_difference_cardinality_vector = [N::ZERO; R];
-        let mut euc: EstimatedUnionCardinalities<N> = EstimatedUnionCardinalities {
-            left: N::ZERO,
-            right: N::ZERO,
-            union: N::ZERO,
-        };
-        let mut last_left_difference = N::ZERO;
-
-        // Populate the overlap cardinality matrix.
-        for (i, left) in lefts.iter().enumerate() {
-            let mut last_right_difference = N::ZERO;
-            let left_cardinality = left.estimate_cardinality();
-            let mut comulative_row = N::ZERO;
-            for (j, (right, right_cardinality)) in
-                rights.iter().zip(right_cardinalities).enumerate()
-            {
-                let union_cardinality = left.estimate_union_cardinality(right);
-                euc = EstimatedUnionCardinalities {
-                    left: left_cardinality,
-                    right: right_cardinality,
-                    union: union_cardinality,
-                };
-                let delta = last_row[j] + comulative_row;
-                differential_overlap_cardinality_matrix[i][j] = euc
-                    .get_intersection_cardinality()
-                    .saturating_zero_sub(delta);
-                last_row[j] = if euc.get_intersection_cardinality() > delta {
-                    euc.get_intersection_cardinality()
-                } else {
-                    delta
-                };
-
-                comulative_row += differential_overlap_cardinality_matrix[i][j];
-                debug_assert!(comulative_row >= N::ZERO, "Expected comulative_row to be larger than zero, but it is not. Got: comulative_row: {comulative_row:?}, delta: {delta:?}");
-
-                // We always set the value of the right difference so that the
-                // last time we write this will necessarily be with the last
-                // and largest left set.
-                right_difference_cardinality_vector[j] = euc
-                    .get_right_difference_cardinality()
-                    .saturating_zero_sub(last_right_difference);
-
-                last_right_difference = euc.get_right_difference_cardinality();
-            }
-            left_difference_cardinality_vector[i] = euc
-                .get_left_difference_cardinality()
-                .saturating_zero_sub(last_left_difference);
-            last_left_difference = euc.get_left_difference_cardinality();
-        }
+        (
+            differential_overlap_cardinality_matrix,
+            left_difference_cardinality_vector,
+            right_difference_cardinality_vector,
+        )
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    /// Returns the overlap and differences cardinality matrices of two lists of sets whose lengths are known at runtime.
+    ///
+    /// # Arguments
+    /// * `left` - The first list of sets.
+    /// * `right` - The second list of sets.
+    ///
+    /// # Returns
+    /// * `overlap_cardinality_matrix` - Row-major matrix with a row for each element of the left slice and a column for each element of the right slice.
+    /// * `left_difference_cardinality_vector` - Vector of estimated difference cardinalities between the elements of the left slice and the last element of the right slice.
+    /// * `right_difference_cardinality_vector` - Vector of estimated difference cardinalities between the elements of the right slice and the last element of the left slice.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    /// # use std::collections::HashSet;
+    ///
+    /// let lefts: Vec<HashSet<u64>> = vec![(0..10).collect(), (0..20).collect()];
+    /// let rights: Vec<HashSet<u64>> = vec![(5..15).collect(), (5..25).collect()];
+    ///
+    /// let (overlaps, left_differences, right_differences) =
+    ///     HashSet::overlap_and_differences_cardinality_matrices_from_slices(&lefts, &rights);
+    ///
+    /// assert_eq!(overlaps.number_of_rows(), 2);
+    /// assert_eq!(overlaps.number_of_columns(), 2);
+    /// assert_eq!(overlaps[(1, 0)], 5.0);
+    /// assert_eq!(left_differences, vec![5.0, 0.0]);
+    /// assert_eq!(right_differences, vec![0.0, 5.0]);
+    /// ```
+    fn overlap_and_differences_cardinality_matrices_from_slices(
+        lefts: &[Self],
+        rights: &[Self],
+    ) -> (RowMajorMatrix<N>, Vec<N>, Vec<N>) {
+        let mut differential_overlap_cardinality_matrix =
+            RowMajorMatrix::new(lefts.len(), rights.len(), N::ZERO);
+        let mut left_difference_cardinality_vector = vec![N::ZERO; lefts.len()];
+        let mut right_difference_cardinality_vector = vec![N::ZERO; rights.len()];
+
+        differential_overlap_and_differences_cardinalities(
+            &lefts
+                .iter()
+                .map(Self::estimate_cardinality)
+                .collect::<Vec<N>>(),
+            &rights
+                .iter()
+                .map(Self::estimate_cardinality)
+                .collect::<Vec<N>>(),
+            &|i, j| lefts[i].estimate_union_cardinality(&rights[j]),
+            differential_overlap_cardinality_matrix.as_mut_slice(),
+            &mut left_difference_cardinality_vector,
+            &mut right_difference_cardinality_vector,
+            &mut vec![N::ZERO; rights.len()],
+        );
 
         (
             differential_overlap_cardinality_matrix,
@@ -120,6 +144,166 @@ pub trait HyperSpheresSketch<N: Number>: Sized {
             right_difference_cardinality_vector,
         )
     }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    /// Writes the overlap and differences cardinalities of the provided edges in the provided buffer.
+    ///
+    /// # Arguments
+    /// * `sketches` - The sketches of the nodes, with the `number_of_hops` sketches of each node stored contiguously.
+    /// * `number_of_hops` - The number of sketches of each node, each contained in the next one.
+    /// * `edges` - The source and destination nodes of each edge.
+    /// * `features` - The buffer to fill, with [`number_of_edge_features`] values for each edge.
+    ///
+    /// # Implementative details
+    /// The features of each edge are the row-major overlap matrix between the sketches of the source
+    /// and destination nodes, followed by the left and right difference vectors. The cardinality of
+    /// each sketch is estimated once and shared by all of the edges.
+    ///
+    /// # Errors
+    /// * If the number of hops is zero or does not divide the number of sketches.
+    /// * If the buffer does not have [`number_of_edge_features`] values for each edge.
+    /// * If an edge refers to a node without sketches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    /// # use std::collections::HashSet;
+    ///
+    /// let sketches: Vec<HashSet<u64>> = vec![
+    ///     (0..10).collect(),
+    ///     (0..20).collect(),
+    ///     (5..15).collect(),
+    ///     (5..25).collect(),
+    /// ];
+    /// let mut features = vec![0.0; 2 * number_of_edge_features(2)];
+    ///
+    /// HashSet::edge_features(&sketches, 2, &[(0, 1), (1, 1)], &mut features).unwrap();
+    ///
+    /// assert_eq!(features[..8], [5.0, 0.0, 5.0, 5.0, 5.0, 0.0, 0.0, 5.0]);
+    /// assert_eq!(features[8..], [10.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, 0.0]);
+    /// ```
+    fn edge_features(
+        sketches: &[Self],
+        number_of_hops: usize,
+        edges: &[(usize, usize)],
+        features: &mut [N],
+    ) -> Result<(), Error> {
+        fill_edge_features(
+            sketches,
+            number_of_hops,
+            edges,
+            features,
+            number_of_hops,
+            differential_overlap_and_differences_cardinalities,
+        )
+    }
+
+    #[inline]
+    #[cfg(feature = "rayon")]
+    /// Writes the overlap and differences cardinalities of the provided edges in the provided buffer, in parallel.
+    ///
+    /// # Arguments
+    /// * `sketches` - The sketches of the nodes, with the `number_of_hops` sketches of each node stored contiguously.
+    /// * `number_of_hops` - The number of sketches of each node, each contained in the next one.
+    /// * `edges` - The source and destination nodes of each edge.
+    /// * `features` - The buffer to fill, with [`number_of_edge_features`] values for each edge.
+    ///
+    /// # Errors
+    /// The same of [`HyperSpheresSketch::edge_features`].
+    fn par_edge_features(
+        sketches: &[Self],
+        number_of_hops: usize,
+        edges: &[(usize, usize)],
+        features: &mut [N],
+    ) -> Result<(), Error>
+    where
+        Self: Sync,
+    {
+        par_fill_edge_features(
+            sketches,
+            number_of_hops,
+            edges,
+            features,
+            number_of_hops,
+            differential_overlap_and_differences_cardinalities,
+        )
+    }
+}
+
+/// Writes the differential overlap and differences cardinalities of two lists of sets.
+///
+/// # Arguments
+/// * `left_cardinalities` - The cardinalities of the first list of sets.
+/// * `right_cardinalities` - The cardinalities of the second list of sets.
+/// * `union_cardinality` - Returns the cardinality of the union of the provided left and right sets.
+/// * `overlaps` - Row-major buffer of the differential overlap cardinalities, with a row for each left set.
+/// * `left_differences` - Buffer of the differential left difference cardinalities.
+/// * `right_differences` - Buffer of the differential right difference cardinalities.
+/// * `last_row` - Scratch buffer for the cumulative intersections of the previous row.
+fn differential_overlap_and_differences_cardinalities<N: Number>(
+    left_cardinalities: &[N],
+    right_cardinalities: &[N],
+    union_cardinality: &dyn Fn(usize, usize) -> N,
+    overlaps: &mut [N],
+    left_differences: &mut [N],
+    right_differences: &mut [N],
+    last_row: &mut [N],
+) {
+    debug_assert_eq!(
+        overlaps.len(),
+        left_cardinalities.len() * right_cardinalities.len()
+    );
+
+    last_row.fill(N::ZERO);
+
+    let mut euc: EstimatedUnionCardinalities<N> = EstimatedUnionCardinalities {
+        left: N::ZERO,
+        right: N::ZERO,
+        union: N::ZERO,
+    };
+    let mut last_left_difference = N::ZERO;
+
+    // Populate the overlap cardinality matrix.
+    for (i, left_cardinality) in left_cardinalities.iter().copied().enumerate() {
+        let row = &mut overlaps[i * right_cardinalities.len()..(i + 1) * right_cardinalities.len()];
+        let mut last_right_difference = N::ZERO;
+        let mut comulative_row = N::ZERO;
+        for (j, right_cardinality) in right_cardinalities.iter().copied().enumerate() {
+            let union_cardinality = union_cardinality(i, j);
+            euc = EstimatedUnionCardinalities {
+                left: left_cardinality,
+                right: right_cardinality,
+                union: union_cardinality,
+            };
+            let delta = last_row[j] + comulative_row;
+            row[j] = euc
+                .get_intersection_cardinality()
+                .saturating_zero_sub(delta);
+            last_row[j] = if euc.get_intersection_cardinality() > delta {
+                euc.get_intersection_cardinality()
+            } else {
+                delta
+            };
+
+            comulative_row += row[j];
+            debug_assert!(comulative_row >= N::ZERO, "Expected comulative_row to be larger than zero, but it is not. Got: comulative_row: {comulative_row:?}, delta: {delta:?}");
+
+            // We always set the value of the right difference so that the
+            // last time we write this will necessarily be with the last
+            // and largest left set.
+            right_differences[j] = euc
+                .get_right_difference_cardinality()
+                .saturating_zero_sub(last_right_difference);
+
+            last_right_difference = euc.get_right_difference_cardinality();
+        }
+        left_differences[i] = euc
+            .get_left_difference_cardinality()
+            .saturating_zero_sub(last_left_difference);
+        last_left_difference = euc.get_left_difference_cardinality();
+    }
 }
 
 /// Trait for sketching algorithms that provide the normalized overlap and differences cardinality matrices.
@@ -139,112 +323,63 @@ pub trait NormalizedHyperSpheresSketch: HyperSpheresSketch<f64> {
         lefts: &[Self; L],
         rights: &[Self; R],
     ) -> ([[f64; R]; L], [f64; L], [f64; R]) {
-        // Initialize overlap and differences cardinality matrices/vectors.
-        let mut last_row = [f64::ZERO; R];
         let mut differential_overlap_cardinality_matrix = [[f64::ZERO; R]; L];
         let mut left_difference_cardinality_vector = [f64::ZERO; L];
-        let mut right_cardinalities = [f64::ZERO; R];
-
-        rights
-            .iter()
-            .zip(right_cardinalities.iter_mut())
-            .for_each(|(right, right_cardinality)| {
-                *right_cardinality = right.estimate_cardinality();
-            });
+        let mut right_difference_cardinality_vector = [f64::ZERO; R];
 
-        // We run a debug assert where we check that each right cardinality is
-        // larger than the previous one.
-        debug_assert!(
-            right_cardinalities
-                .iter()
-                .zip(right_cardinalities.iter().skip(1))
-                .all(|(left, right)| left <= right),
-            "The right cardinalities should be sorted in ascending order."
+        normalized_differential_overlap_and_differences_cardinalities(
+            &lefts.each_ref().map(Self::estimate_cardinality),
+            &rights.each_ref().map(Self::estimate_cardinality),
+            &|i, j| lefts[i].estimate_union_cardinality(&rights[j]),
+            differential_overlap_cardinality_matrix.as_flattened_mut(),
+            &mut left_difference_cardinality_vector,
+            &mut right_difference_cardinality_vector,
+            [[f64::ZERO; R]; 2].as_flattened_mut(),
         );
 
-        let mut right_difference_cardinality_vector = [f64::ZERO; R];
-        let mut euc: EstimatedUnionCardinalities<f64> = EstimatedUnionCardinalities {
-            left: f64::ZERO,
-            right: f64::ZERO,
-            union: f64::ZERO,
-        };
-        let mut last_left_difference = f64::ZERO;
-        let mut last_inner_left_differences = [f64::ZERO; R];
-        let mut last_left_cardinality = f64::ZERO;
-
-        // Populate the overlap cardinality matrix.
-        for (i, left) in lefts.iter().enumerate() {
-            let mut last_right_difference = f64::ZERO;
-            let left_cardinality = left.estimate_cardinality();
-            let mut comulative_row = f64::ZERO;
-            let mut last_right_cardinality = f64::ZERO;
-            for (j, (right, (right_cardinality, last_inner_left_difference))) in rights
-                .iter()
-                .zip(
-                    right_cardinalities
-                        .iter()
-                        .copied()
-                        .zip(last_inner_left_differences.iter_mut()),
-                )
-                .enumerate()
-            {
-                let union_cardinality = left.estimate_union_cardinality(right);
-                euc = EstimatedUnionCardinalities {
-                    left: left_cardinality,
-                    right: right_cardinality,
-                    union: union_cardinality,
-                };
-                let delta = last_row[j] + comulative_row;
-                let differential_intersection = euc
-                    .get_intersection_cardinality()
-                    .saturating_zero_sub(delta);
-
-                debug_assert!(
-                    differential_intersection >= f64::ZERO,
-                    concat!(
-                        "Expected differential_intersection to be larger than zero, but it is not. ",
-                        "Got: differential_intersection: {:?}, delta: {:?}",
-                    ),
-                    differential_intersection,
-                    delta,
-                );
+        (
+            differential_overlap_cardinality_matrix,
+            left_difference_cardinality_vector,
+            right_difference_cardinality_vector,
+        )
+    }
 
-                let maximal_differential_intersection_cardinality =
-                    (euc.get_left_difference_cardinality() + right_cardinality)
-                        .saturating_zero_sub(*last_inner_left_difference + last_right_cardinality);
-                *last_inner_left_difference = euc.get_left_difference_cardinality();
-
-                differential_overlap_cardinality_matrix[i][j] = differential_intersection
-                    .saturating_one_div(maximal_differential_intersection_cardinality);
-                last_row[j] = if euc.get_intersection_cardinality() > delta {
-                    euc.get_intersection_cardinality()
-                } else {
-                    delta
-                };
-                comulative_row += differential_intersection;
-
-                // We always set the value of the right difference so that the
-                // last time we write this will necessarily be with the last
-                // and largest left set.
-
-                let differential_right_difference = euc
-                    .get_right_difference_cardinality()
-                    .saturating_zero_sub(last_right_difference);
-
-                right_difference_cardinality_vector[j] = differential_right_difference
-                    .saturating_one_div(
-                        right_cardinality.saturating_zero_sub(last_right_cardinality),
-                    );
-                last_right_difference = euc.get_right_difference_cardinality();
-                last_right_cardinality = right_cardinality;
-            }
-            left_difference_cardinality_vector[i] = euc
-                .get_left_difference_cardinality()
-                .saturating_zero_sub(last_left_difference)
-                .saturating_one_div(left_cardinality.saturating_zero_sub(last_left_cardinality));
-            last_left_cardinality = left_cardinality;
-            last_left_difference = euc.get_left_difference_cardinality();
-        }
+    #[inline]
+    #[cfg(feature = "alloc")]
+    /// Returns the normalized overlap and differences cardinality matrices of two lists of sets whose lengths are known at runtime.
+    ///
+    /// # Arguments
+    /// * `left` - The first list of sets.
+    /// * `right` - The second list of sets.
+    ///
+    /// # Returns
+    /// * `overlap_cardinality_matrix` - Row-major matrix with a row for each element of the left slice and a column for each element of the right slice.
+    /// * `left_difference_cardinality_vector` - Vector of normalized estimated difference cardinalities between the elements of the left slice and the last element of the right slice.
+    /// * `right_difference_cardinality_vector` - Vector of normalized estimated difference cardinalities between the elements of the right slice and the last element of the left slice.
+    fn normalized_overlap_and_differences_cardinality_matrices_from_slices(
+        lefts: &[Self],
+        rights: &[Self],
+    ) -> (RowMajorMatrix<f64>, Vec<f64>, Vec<f64>) {
+        let mut differential_overlap_cardinality_matrix =
+            RowMajorMatrix::new(lefts.len(), rights.len(), f64::ZERO);
+        let mut left_difference_cardinality_vector = vec![f64::ZERO; lefts.len()];
+        let mut right_difference_cardinality_vector = vec![f64::ZERO; rights.len()];
+
+        normalized_differential_overlap_and_differences_cardinalities(
+            &lefts
+                .iter()
+                .map(Self::estimate_cardinality)
+                .collect::<Vec<f64>>(),
+            &rights
+                .iter()
+                .map(Self::estimate_cardinality)
+                .collect::<Vec<f64>>(),
+            &|i, j| lefts[i].estimate_union_cardinality(&rights[j]),
+            differential_overlap_cardinality_matrix.as_mut_slice(),
+            &mut left_difference_cardinality_vector,
+            &mut right_difference_cardinality_vector,
+            &mut vec![f64::ZERO; 2 * rights.len()],
+        );
 
         (
             differential_overlap_cardinality_matrix,
@@ -252,8 +387,404 @@ pub trait NormalizedHyperSpheresSketch: HyperSpheresSketch<f64> {
             right_difference_cardinality_vector,
         )
     }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    /// Writes the normalized overlap and differences cardinalities of the provided edges in the provided buffer.
+    ///
+    /// # Arguments
+    /// * `sketches` - The sketches of the nodes, with the `number_of_hops` sketches of each node stored contiguously.
+    /// * `number_of_hops` - The number of sketches of each node, each contained in the next one.
+    /// * `edges` - The source and destination nodes of each edge.
+    /// * `features` - The buffer to fill, with [`number_of_edge_features`] values for each edge.
+    ///
+    /// # Errors
+    /// The same of [`HyperSpheresSketch::edge_features`].
+    fn normalized_edge_features(
+        sketches: &[Self],
+        number_of_hops: usize,
+        edges: &[(usize, usize)],
+        features: &mut [f64],
+    ) -> Result<(), Error> {
+        fill_edge_features(
+            sketches,
+            number_of_hops,
+            edges,
+            features,
+            2 * number_of_hops,
+            normalized_differential_overlap_and_differences_cardinalities,
+        )
+    }
+
+    #[inline]
+    #[cfg(feature = "rayon")]
+    /// Writes the normalized overlap and differences cardinalities of the provided edges in the provided buffer, in parallel.
+    ///
+    /// # Arguments
+    /// * `sketches` - The sketches of the nodes, with the `number_of_hops` sketches of each node stored contiguously.
+    /// * `number_of_hops` - The number of sketches of each node, each contained in the next one.
+    /// * `edges` - The source and destination nodes of each edge.
+    /// * `features` - The buffer to fill, with [`number_of_edge_features`] values for each edge.
+    ///
+    /// # Errors
+    /// The same of [`HyperSpheresSketch::edge_features`].
+    fn par_normalized_edge_features(
+        sketches: &[Self],
+        number_of_hops: usize,
+        edges: &[(usize, usize)],
+        features: &mut [f64],
+    ) -> Result<(), Error>
+    where
+        Self: Sync,
+    {
+        par_fill_edge_features(
+            sketches,
+            number_of_hops,
+            edges,
+            features,
+            2 * number_of_hops,
+            normalized_differential_overlap_and_differences_cardinalities,
+        )
+    }
+}
+
+/// Writes the normalized differential overlap and differences cardinalities of two lists of sets.
+///
+/// # Arguments
+/// * `left_cardinalities` - The cardinalities of the first list of sets.
+/// * `right_cardinalities` - The cardinalities of the second list of sets.
+/// * `union_cardinality` - Returns the cardinality of the union of the provided left and right sets.
+/// * `overlaps` - Row-major buffer of the normalized overlap cardinalities, with a row for each left set.
+/// * `left_differences` - Buffer of the normalized left difference cardinalities.
+/// * `right_differences` - Buffer of the normalized right difference cardinalities.
+/// * `scratch` - Scratch buffer with twice as many values as the right sets.
+fn normalized_differential_overlap_and_differences_cardinalities(
+    left_cardinalities: &[f64],
+    right_cardinalities: &[f64],
+    union_cardinality: &dyn Fn(usize, usize) -> f64,
+    overlaps: &mut [f64],
+    left_differences: &mut [f64],
+    right_differences: &mut [f64],
+    scratch: &mut [f64],
+) {
+    debug_assert_eq!(
+        overlaps.len(),
+        left_cardinalities.len() * right_cardinalities.len()
+    );
+
+    scratch.fill(f64::ZERO);
+    let (last_row, last_inner_left_differences) = scratch.split_at_mut(right_cardinalities.len());
+
+    // We run a debug assert where we check that each right cardinality is
+    // larger than the previous one.
+    debug_assert!(
+        right_cardinalities
+            .iter()
+            .zip(right_cardinalities.iter().skip(1))
+            .all(|(left, right)| left <= right),
+        "The right cardinalities should be sorted in ascending order."
+    );
+
+    let mut euc: EstimatedUnionCardinalities<f64> = EstimatedUnionCardinalities {
+        left: f64::ZERO,
+        right: f64::ZERO,
+        union: f64::ZERO,
+    };
+    let mut last_left_difference = f64::ZERO;
+    let mut last_left_cardinality = f64::ZERO;
+
+    // Populate the overlap cardinality matrix.
+    for (i, left_cardinality) in left_cardinalities.iter().copied().enumerate() {
+        let row = &mut overlaps[i * right_cardinalities.len()..(i + 1) * right_cardinalities.len()];
+        let mut last_right_difference = f64::ZERO;
+        let mut comulative_row = f64::ZERO;
+        let mut last_right_cardinality = f64::ZERO;
+        for (j, (right_cardinality, last_inner_left_difference)) in right_cardinalities
+            .iter()
+            .copied()
+            .zip(last_inner_left_differences.iter_mut())
+            .enumerate()
+        {
+            let union_cardinality = union_cardinality(i, j);
+            euc = EstimatedUnionCardinalities {
+                left: left_cardinality,
+                right: right_cardinality,
+                union: union_cardinality,
+            };
+            let delta = last_row[j] + comulative_row;
+            let differential_intersection = euc
+                .get_intersection_cardinality()
+                .saturating_zero_sub(delta);
+
+            debug_assert!(
+                differential_intersection >= f64::ZERO,
+                concat!(
+                    "Expected differential_intersection to be larger than zero, but it is not. ",
+                    "Got: differential_intersection: {:?}, delta: {:?}",
+                ),
+                differential_intersection,
+                delta,
+            );
+
+            let maximal_differential_intersection_cardinality =
+                (euc.get_left_difference_cardinality() + right_cardinality)
+                    .saturating_zero_sub(*last_inner_left_difference + last_right_cardinality);
+            *last_inner_left_difference = euc.get_left_difference_cardinality();
+
+            row[j] = differential_intersection
+                .saturating_one_div(maximal_differential_intersection_cardinality);
+            last_row[j] = if euc.get_intersection_cardinality() > delta {
+                euc.get_intersection_cardinality()
+            } else {
+                delta
+            };
+            comulative_row += differential_intersection;
+
+            // We always set the value of the right difference so that the
+            // last time we write this will necessarily be with the last
+            // and largest left set.
+
+            let differential_right_difference = euc
+                .get_right_difference_cardinality()
+                .saturating_zero_sub(last_right_difference);
+
+            right_differences[j] = differential_right_difference
+                .saturating_one_div(right_cardinality.saturating_zero_sub(last_right_cardinality));
+            last_right_difference = euc.get_right_difference_cardinality();
+            last_right_cardinality = right_cardinality;
+        }
+        left_differences[i] = euc
+            .get_left_difference_cardinality()
+            .saturating_zero_sub(last_left_difference)
+            .saturating_one_div(left_cardinality.saturating_zero_sub(last_left_cardinality));
+        last_left_cardinality = left_cardinality;
+        last_left_difference = euc.get_left_difference_cardinality();
+    }
+}
+
+#[must_use]
+/// Returns the number of features of an edge between two nodes with the provided number of hops.
+///
+/// The features of an edge are the overlap matrix between the hops of the two nodes,
+/// followed by the left and right difference vectors.
+pub const fn number_of_edge_features(number_of_hops: usize) -> usize {
+    number_of_hops * (number_of_hops + 2)
+}
+
+#[cfg(feature = "alloc")]
+/// Signature of the functions writing the differential overlap and differences cardinalities.
+type DifferentialCardinalities<N> =
+    fn(&[N], &[N], &dyn Fn(usize, usize) -> N, &mut [N], &mut [N], &mut [N], &mut [N]);
+
+#[cfg(feature = "alloc")]
+/// Checks that the provided sketches, edges and features buffer are consistent.
+///
+/// # Arguments
+/// * `number_of_sketches` - The number of sketches of all of the nodes.
+/// * `number_of_hops` - The number of sketches of each node.
+/// * `edges` - The source and destination nodes of each edge.
+/// * `number_of_features` - The number of values in the features buffer.
+fn validate_edge_features(
+    number_of_sketches: usize,
+    number_of_hops: usize,
+    edges: &[(usize, usize)],
+    number_of_features: usize,
+) -> Result<(), Error> {
+    if number_of_hops == 0 {
+        return Err(Error::InvalidArgument(
+            "The number of hops must be positive.",
+        ));
+    }
+    if !number_of_sketches.is_multiple_of(number_of_hops) {
+        return Err(Error::InvalidArgument(
+            "The number of sketches must be a multiple of the number of hops.",
+        ));
+    }
+    if number_of_features != edges.len() * number_of_edge_features(number_of_hops) {
+        return Err(Error::InvalidArgument(
+            "The features buffer must have the features of each edge.",
+        ));
+    }
+    let number_of_nodes = number_of_sketches / number_of_hops;
+    if edges
+        .iter()
+        .any(|&(source, destination)| source >= number_of_nodes || destination >= number_of_nodes)
+    {
+        return Err(Error::InvalidArgument(
+            "The edges must refer to nodes with sketches.",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+/// Writes the features of a single edge.
+///
+/// # Arguments
+/// * `sketches` - The sketches of the nodes.
+/// * `cardinalities` - The cardinalities of the sketches of the nodes.
+/// * `number_of_hops` - The number of sketches of each node.
+/// * `edge` - The source and destination nodes of the edge.
+/// * `features` - The buffer to fill with the features of the edge.
+/// * `scratch` - Scratch buffer of the differential cardinalities function.
+/// * `differential_cardinalities` - The function writing the differential cardinalities.
+fn fill_single_edge_features<N: Number, S: HyperSpheresSketch<N>>(
+    sketches: &[S],
+    cardinalities: &[N],
+    number_of_hops: usize,
+    (source, destination): (usize, usize),
+    features: &mut [N],
+    scratch: &mut [N],
+    differential_cardinalities: DifferentialCardinalities<N>,
+) {
+    let lefts = source * number_of_hops..(source + 1) * number_of_hops;
+    let rights = destination * number_of_hops..(destination + 1) * number_of_hops;
+    let (overlaps, differences) = features.split_at_mut(number_of_hops * number_of_hops);
+    let (left_differences, right_differences) = differences.split_at_mut(number_of_hops);
+    let (left_sketches, right_sketches) = (&sketches[lefts.clone()], &sketches[rights.clone()]);
+
+    differential_cardinalities(
+        &cardinalities[lefts],
+        &cardinalities[rights],
+        &|i, j| left_sketches[i].estimate_union_cardinality(&right_sketches[j]),
+        overlaps,
+        left_differences,
+        right_differences,
+        scratch,
+    );
+}
+
+#[cfg(feature = "alloc")]
+/// Writes the features of the provided edges, estimating the cardinality of each sketch once.
+///
+/// # Arguments
+/// * `sketches` - The sketches of the nodes.
+/// * `number_of_hops` - The number of sketches of each node.
+/// * `edges` - The source and destination nodes of each edge.
+/// * `features` - The buffer to fill with the features of the edges.
+/// * `scratch_size` - The size of the scratch buffer of the differential cardinalities function.
+/// * `differential_cardinalities` - The function writing the differential cardinalities.
+fn fill_edge_features<N: Number, S: HyperSpheresSketch<N>>(
+    sketches: &[S],
+    number_of_hops: usize,
+    edges: &[(usize, usize)],
+    features: &mut [N],
+    scratch_size: usize,
+    differential_cardinalities: DifferentialCardinalities<N>,
+) -> Result<(), Error> {
+    validate_edge_features(sketches.len(), number_of_hops, edges, features.len())?;
+
+    let cardinalities = sketches
+        .iter()
+        .map(S::estimate_cardinality)
+        .collect::<Vec<N>>();
+    let mut scratch = vec![N::ZERO; scratch_size];
+
+    for (edge_features, &edge) in features
+        .chunks_exact_mut(number_of_edge_features(number_of_hops))
+        .zip(edges)
+    {
+        fill_single_edge_features(
+            sketches,
+            &cardinalities,
+            number_of_hops,
+            edge,
+            edge_features,
+            &mut scratch,
+            differential_cardinalities,
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "rayon")]
+/// Writes the features of the provided edges in parallel, estimating the cardinality of each sketch once.
+///
+/// # Arguments
+/// * `sketches` - The sketches of the nodes.
+/// * `number_of_hops` - The number of sketches of each node.
+/// * `edges` - The source and destination nodes of each edge.
+/// * `features` - The buffer to fill with the features of the edges.
+/// * `scratch_size` - The size of the scratch buffer of the differential cardinalities function.
+/// * `differential_cardinalities` - The function writing the differential cardinalities.
+fn par_fill_edge_features<N: Number, S: HyperSpheresSketch<N> + Sync>(
+    sketches: &[S],
+    number_of_hops: usize,
+    edges: &[(usize, usize)],
+    features: &mut [N],
+    scratch_size: usize,
+    differential_cardinalities: DifferentialCardinalities<N>,
+) -> Result<(), Error> {
+    validate_edge_features(sketches.len(), number_of_hops, edges, features.len())?;
+
+    let cardinalities = sketches
+        .par_iter()
+        .map(S::estimate_cardinality)
+        .collect::<Vec<N>>();
+
+    features
+        .par_chunks_mut(number_of_edge_features(number_of_hops))
+        .zip(edges.par_iter())
+        .for_each_init(
+            || vec![N::ZERO; scratch_size],
+            |scratch, (edge_features, &edge)| {
+                fill_single_edge_features(
+                    sketches,
+                    &cardinalities,
+                    number_of_hops,
+                    edge,
+                    edge_features,
+                    scratch,
+                    differential_cardinalities,
+                );
+            },
+        );
+
+    Ok(())
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperSpheresSketch<f64>
+    for HyperLogLog<P, B, R, H>
+{
+    #[inline]
+    fn estimate_cardinality(&self) -> f64 {
+        HyperLogLog::estimate_cardinality(self)
+    }
+
+    #[inline]
+    fn estimate_union_cardinality(&self, other: &Self) -> f64 {
+        HyperLogLog::estimate_union_cardinality(self, other)
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> NormalizedHyperSpheresSketch
+    for HyperLogLog<P, B, R, H>
+{
+}
+
+#[cfg(feature = "std")]
+impl<T: Eq + Hash, S: BuildHasher> HyperSpheresSketch<f64> for HashSet<T, S> {
+    #[inline]
+    // Sets are expected to hold fewer than 2^53 elements, whose count is exactly representable.
+    #[allow(clippy::cast_precision_loss)]
+    /// Returns the exact cardinality of the set.
+    fn estimate_cardinality(&self) -> f64 {
+        self.len() as f64
+    }
+
+    #[inline]
+    // As for the cardinality, unions of fewer than 2^53 elements are exactly representable.
+    #[allow(clippy::cast_precision_loss)]
+    /// Returns the exact cardinality of the union of the two sets.
+    fn estimate_union_cardinality(&self, other: &Self) -> f64 {
+        self.union(other).count() as f64
+    }
 }
 
+#[cfg(feature = "std")]
+impl<T: Eq + Hash, S: BuildHasher> NormalizedHyperSpheresSketch for HashSet<T, S> {}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -288,3 +819,240 @@ impl<F: Number> EstimatedUnionCardinalities<F> {
         self.union - self.left
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use hyperloglog_derive::test_estimator;
+    use twox_hash::XxHash;
+
+    #[test]
+    fn test_hash_set_overlap_and_differences_cardinality_matrices() {
+        let lefts: [HashSet<u64>; 2] = [(0..10).collect(), (0..20).collect()];
+        let rights: [HashSet<u64>; 2] = [(5..15).collect(), (5..25).collect()];
+
+        let (overlaps, left_differences, right_differences) =
+            HashSet::overlap_and_differences_cardinality_matrices(&lefts, &rights);
+
+        assert_eq!(
+            overlaps.map(|row| row.map(f64::to_bits)),
+            [[5.0, 0.0], [5.0, 5.0]].map(|row| row.map(f64::to_bits))
+        );
+        assert_eq!(
+            left_differences.map(f64::to_bits),
+            [5.0, 0.0].map(f64::to_bits)
+        );
+        assert_eq!(
+            right_differences.map(f64::to_bits),
+            [0.0, 5.0].map(f64::to_bits)
+        );
+
+        let (overlaps, left_differences, right_differences) =
+            HashSet::normalized_overlap_and_differences_cardinality_matrices(&lefts, &rights);
+
+        assert_eq!(
+            overlaps.map(|row| row.map(f64::to_bits)),
+            [[1.0 / 3.0, 0.0], [1.0 / 3.0, 0.5]].map(|row| row.map(f64::to_bits))
+        );
+        assert_eq!(
+            left_differences.map(f64::to_bits),
+            [0.5, 0.0].map(f64::to_bits)
+        );
+        assert_eq!(
+            right_differences.map(f64::to_bits),
+            [0.0, 0.5].map(f64::to_bits)
+        );
+    }
+
+    #[test_estimator]
+    fn test_overlap_and_differences_cardinality_matrices_from_slices<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let lefts: [HyperLogLog<P, B, R, H>; 3] = [
+            (0_u64..100).collect(),
+            (0..1_000).collect(),
+            (0..2_000).collect(),
+        ];
+        let rights: [HyperLogLog<P, B, R, H>; 2] = [(50_u64..500).collect(), (50..3_000).collect()];
+
+        let (overlaps, left_differences, right_differences) =
+            HyperLogLog::overlap_and_differences_cardinality_matrices(&lefts, &rights);
+        let (sliced_overlaps, sliced_left_differences, sliced_right_differences) =
+            HyperLogLog::overlap_and_differences_cardinality_matrices_from_slices(&lefts, &rights);
+
+        assert_eq!(sliced_overlaps.number_of_rows(), 3);
+        assert_eq!(sliced_overlaps.number_of_columns(), 2);
+        assert!(sliced_overlaps
+            .rows()
+            .eq(overlaps.iter().map(|row| &row[..])));
+        assert_eq!(sliced_left_differences, left_differences);
+        assert_eq!(sliced_right_differences, right_differences);
+
+        let (overlaps, left_differences, right_differences) =
+            HyperLogLog::normalized_overlap_and_differences_cardinality_matrices(&lefts, &rights);
+        let (sliced_overlaps, sliced_left_differences, sliced_right_differences) =
+            HyperLogLog::normalized_overlap_and_differences_cardinality_matrices_from_slices(
+                &lefts, &rights,
+            );
+
+        assert!(sliced_overlaps
+            .rows()
+            .eq(overlaps.iter().map(|row| &row[..])));
+        assert_eq!(sliced_left_differences, left_differences);
+        assert_eq!(sliced_right_differences, right_differences);
+
+        // The runtime-sized variants also support lists of sets of any length.
+        let (overlaps, left_differences, right_differences) =
+            HyperLogLog::overlap_and_differences_cardinality_matrices_from_slices(
+                &lefts[..0],
+                &rights,
+            );
+        assert_eq!(overlaps.number_of_rows(), 0);
+        assert!(left_differences.is_empty());
+        assert_eq!(right_differences, [0.0, 0.0]);
+    }
+
+    #[test_estimator]
+    fn test_edge_features<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let number_of_hops = 3;
+        let number_of_nodes = 4_u64;
+        let sketches: Vec<HyperLogLog<P, B, R, H>> = (0..number_of_nodes)
+            .flat_map(|node| {
+                (1..=number_of_hops as u64)
+                    .map(move |hop| (node * 100..node * 100 + hop * 150).collect())
+            })
+            .collect();
+        let edges = [(0, 1), (1, 0), (2, 3), (3, 3), (0, 3)];
+
+        let mut features = vec![0.0; edges.len() * number_of_edge_features(number_of_hops)];
+        let mut normalized_features = features.clone();
+        HyperLogLog::edge_features(&sketches, number_of_hops, &edges, &mut features).unwrap();
+        HyperLogLog::normalized_edge_features(
+            &sketches,
+            number_of_hops,
+            &edges,
+            &mut normalized_features,
+        )
+        .unwrap();
+
+        for (((source, destination), edge_features), normalized_edge_features) in edges
+            .iter()
+            .zip(features.chunks(number_of_edge_features(number_of_hops)))
+            .zip(normalized_features.chunks(number_of_edge_features(number_of_hops)))
+        {
+            let lefts = &sketches[source * number_of_hops..(source + 1) * number_of_hops];
+            let rights =
+                &sketches[destination * number_of_hops..(destination + 1) * number_of_hops];
+
+            let (overlaps, left_differences, right_differences) =
+                HyperLogLog::overlap_and_differences_cardinality_matrices_from_slices(
+                    lefts, rights,
+                );
+            let expected = [overlaps.as_slice(), &left_differences, &right_differences].concat();
+            assert_eq!(edge_features, expected);
+
+            let (overlaps, left_differences, right_differences) =
+                HyperLogLog::normalized_overlap_and_differences_cardinality_matrices_from_slices(
+                    lefts, rights,
+                );
+            let expected = [overlaps.as_slice(), &left_differences, &right_differences].concat();
+            assert_eq!(normalized_edge_features, expected);
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut par_features = vec![0.0; features.len()];
+            HyperLogLog::par_edge_features(&sketches, number_of_hops, &edges, &mut par_features)
+                .unwrap();
+            assert_eq!(par_features, features);
+
+            HyperLogLog::par_normalized_edge_features(
+                &sketches,
+                number_of_hops,
+                &edges,
+                &mut par_features,
+            )
+            .unwrap();
+            assert_eq!(par_features, normalized_features);
+        }
+
+        assert!(HyperLogLog::edge_features(&sketches, 0, &edges, &mut features).is_err());
+        assert!(HyperLogLog::edge_features(&sketches, 5, &edges, &mut features).is_err());
+        assert!(
+            HyperLogLog::edge_features(&sketches, number_of_hops, &edges[..1], &mut features)
+                .is_err()
+        );
+        assert!(HyperLogLog::edge_features(
+            &sketches,
+            number_of_hops,
+            &[(0, 4)],
+            &mut features[..number_of_edge_features(number_of_hops)]
+        )
+        .is_err());
+    }
+
+    #[test_estimator]
+    fn test_hyperloglog_overlap_and_differences_cardinality_matrices<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        H: HasherType + Default,
+    >() {
+        let ranges = [(0_u64, 1_000_u64), (0, 2_000), (500, 1_500), (500, 3_000)];
+        let exact: [HashSet<u64>; 4] = ranges.map(|(start, end)| (start..end).collect());
+        // The counters are kept on the heap, since a few counters with the larger precisions
+        // and their copies would overflow the stack of the test threads.
+        let approximated: Vec<HyperLogLog<P, B, R, H>> = ranges
+            .iter()
+            .map(|&(start, end)| (start..end).collect())
+            .collect();
+        let lefts: &[HyperLogLog<P, B, R, H>; 2] = approximated[..2].try_into().unwrap();
+        let rights: &[HyperLogLog<P, B, R, H>; 2] = approximated[2..].try_into().unwrap();
+
+        let (exact_overlaps, exact_left_differences, exact_right_differences) =
+            HashSet::overlap_and_differences_cardinality_matrices(
+                &[exact[0].clone(), exact[1].clone()],
+                &[exact[2].clone(), exact[3].clone()],
+            );
+        let (overlaps, left_differences, right_differences) =
+            HyperLogLog::overlap_and_differences_cardinality_matrices(lefts, rights);
+
+        // Each differential cardinality combines several estimates, each within
+        // a few error rates of the size of the largest union.
+        let tolerance = 12.0 * P::error_rate() * 3_000.0;
+
+        for (row, exact_row) in overlaps.iter().zip(exact_overlaps.iter()) {
+            for (value, exact_value) in row.iter().zip(exact_row.iter()) {
+                assert!(
+                    (value - exact_value).abs() <= tolerance,
+                    "Overlap {value} too far from {exact_value}."
+                );
+            }
+        }
+        for (value, exact_value) in left_differences.iter().chain(right_differences.iter()).zip(
+            exact_left_differences
+                .iter()
+                .chain(exact_right_differences.iter()),
+        ) {
+            assert!(
+                (value - exact_value).abs() <= tolerance,
+                "Difference {value} too far from {exact_value}."
+            );
+        }
+
+        let (overlaps, left_differences, right_differences) =
+            HyperLogLog::normalized_overlap_and_differences_cardinality_matrices(lefts, rights);
+
+        assert!(overlaps
+            .iter()
+            .flatten()
+            .chain(left_differences.iter())
+            .chain(right_differences.iter())
+            .all(|value| (0.0..=1.0).contains(value)));
+    }
+}
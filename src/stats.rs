@@ -0,0 +1,197 @@
+//! Submodule providing the [`Statistics`] of the internal state of a [`HyperLogLog`] counter.
+use crate::composite_hash::GapHash;
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use core::mem::size_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The statistics describing the internal state of a counter, as returned by [`HyperLogLog::stats`].
+pub struct Statistics {
+    /// The mode the counter is in.
+    pub mode: Mode,
+    /// The exponent of the number of registers.
+    pub precision: u8,
+    /// The number of bits per register.
+    pub bits: u8,
+    /// The number of bits of the stored hashes, in hash list mode.
+    pub hash_bits: Option<u8>,
+    /// The number of stored hashes, in hash list mode.
+    pub number_of_hashes: Option<u32>,
+    /// The number of hashes merged into others when downgrading the hash bits, in hash list mode.
+    pub duplicates: Option<u32>,
+    /// The number of bits used by the hash list or by the registers.
+    pub used_bits: usize,
+    /// The number of bits currently available in the storage of the counter.
+    pub capacity_bits: usize,
+    /// Whether the hash list is encoded with prefix-free codes.
+    pub prefix_free: bool,
+    /// The number of registers with each value, indexed by the value.
+    pub register_histogram: [u32; 64],
+    /// The number of registers equal to zero.
+    pub number_of_zero_registers: usize,
+    /// The number of bytes used by the counter, including the ones allocated on the heap.
+    pub memory_footprint: usize,
+}
+
+impl Display for Statistics {
+    #[inline]
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            formatter,
+            "mode={}, precision={}, bits={}",
+            self.mode, self.precision, self.bits
+        )?;
+        if let Some(hash_bits) = self.hash_bits {
+            write!(formatter, ", hash_bits={hash_bits}")?;
+        }
+        if let Some(number_of_hashes) = self.number_of_hashes {
+            write!(formatter, ", hashes={number_of_hashes}")?;
+        }
+        if let Some(duplicates) = self.duplicates {
+            write!(formatter, ", duplicates={duplicates}")?;
+        }
+        write!(
+            formatter,
+            ", used_bits={}/{}, prefix_free={}, zero_registers={}, histogram=[",
+            self.used_bits, self.capacity_bits, self.prefix_free, self.number_of_zero_registers
+        )?;
+        let mut separator = "";
+        for (value, count) in self.register_histogram.iter().enumerate() {
+            if *count > 0 {
+                write!(formatter, "{separator}{value}:{count}")?;
+                separator = " ";
+            }
+        }
+        write!(formatter, "], memory={}B", self.memory_footprint)
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B, R, H> {
+    #[inline]
+    #[must_use]
+    /// Returns the statistics describing the internal state of the counter.
+    ///
+    /// # Implementative details
+    /// In hash list mode, the register histogram and the number of zero registers describe the
+    /// registers the hash list would be converted to, and the used bits are the bits written in
+    /// the hash list. In registers mode, the used bits are the bits of the packed registers.
+    /// The memory footprint includes the storage of the registers allocated on the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll: HyperLogLog<Precision8, Bits6, <Precision8 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// hll.extend(0..3);
+    ///
+    /// let stats = hll.stats();
+    /// assert_eq!(stats.mode, Mode::HashList);
+    /// assert_eq!(stats.number_of_hashes, Some(3));
+    /// assert_eq!(stats.number_of_zero_registers, 256 - 3);
+    /// assert!(stats.to_string().starts_with("mode=hash list, precision=8, bits=6"));
+    /// ```
+    pub fn stats(&self) -> Statistics {
+        let storage_size = <R as AsRef<[u8]>>::as_ref(&self.registers).len();
+        let register_histogram = self.register_histogram();
+        let mut stats = Statistics {
+            mode: self.mode(),
+            precision: P::EXPONENT,
+            bits: B::NUMBER_OF_BITS,
+            hash_bits: None,
+            number_of_hashes: None,
+            duplicates: None,
+            used_bits: (1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE,
+            capacity_bits: storage_size * 8,
+            prefix_free: false,
+            register_histogram,
+            number_of_zero_registers: register_histogram[0] as usize,
+            memory_footprint: size_of::<Self>() - size_of::<R>()
+                + self.registers.memory_footprint(),
+        };
+
+        if let (Ok(hash_bits), Ok(number_of_hashes)) =
+            (self.get_hash_bits(), self.get_number_of_hashes())
+        {
+            let writer_tell = self.get_writer_tell();
+            stats.hash_bits = Some(hash_bits);
+            stats.number_of_hashes = Some(number_of_hashes);
+            stats.duplicates = Some(self.get_duplicates());
+            stats.used_bits = writer_tell as usize;
+            stats.prefix_free =
+                GapHash::<P, B>::is_prefix_free_encoded(number_of_hashes, hash_bits, writer_tell);
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_estimator;
+    use twox_hash::XxHash;
+
+    #[test_estimator]
+    fn test_stats<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
+        let mut hll: HyperLogLog<P, B, R, H> = HyperLogLog::default();
+        let stats = hll.stats();
+        assert_eq!(stats.mode, Mode::HashList);
+        assert_eq!(stats.number_of_hashes, Some(0));
+        assert_eq!(stats.duplicates, Some(0));
+        assert_eq!(stats.used_bits, 0);
+        assert_eq!(stats.number_of_zero_registers, 1 << P::EXPONENT);
+        assert!(stats.memory_footprint >= size_of::<HyperLogLog<P, B, R, H>>());
+
+        let element = |position: u64| splitmix64(splitmix64(position));
+        let capacity = HyperLogLog::<P, B, R, H>::hash_list_capacity(element);
+        let mut inserted = 0;
+        // The smallest and the largest hash lists, and the first registers.
+        for size in [1, capacity, capacity + 1] {
+            hll.extend((inserted..size).map(element));
+            inserted = size;
+
+            let stats = hll.stats();
+            let mut registers = hll.clone();
+            if hll.is_hash_list() {
+                assert_eq!(stats.hash_bits, Some(hll.get_hash_bits().unwrap()));
+                assert_eq!(
+                    stats.number_of_hashes,
+                    Some(hll.get_number_of_hashes().unwrap())
+                );
+                assert_eq!(stats.used_bits, hll.get_writer_tell() as usize);
+                registers.convert_hash_list_to_hyperloglog().unwrap();
+            } else {
+                assert_eq!(stats.mode, Mode::Registers);
+                assert_eq!(stats.hash_bits, None);
+                assert!(!stats.prefix_free);
+                assert_eq!(
+                    stats.used_bits,
+                    (1 << P::EXPONENT) * B::NUMBER_OF_BITS_USIZE
+                );
+            }
+            assert!(stats.used_bits <= stats.capacity_bits);
+
+            // The histogram describes the registers the counter has or would be converted to.
+            let mut histogram = [0_u32; 64];
+            for register in registers.registers.iter_registers() {
+                histogram[usize::from(register)] += 1;
+            }
+            assert_eq!(stats.register_histogram, histogram);
+            assert_eq!(
+                stats.number_of_zero_registers,
+                registers.number_of_zero_registers().unwrap()
+            );
+
+            let report = stats.to_string();
+            assert!(report.starts_with(&format!("mode={}", stats.mode)));
+            assert!(report.contains(&format!(
+                "zero_registers={}",
+                stats.number_of_zero_registers
+            )));
+            assert_eq!(report.contains("hash_bits="), hll.is_hash_list());
+        }
+    }
+}
@@ -13,14 +13,20 @@ mod intersection_from_sorted_iterators;
 mod matrix;
 mod number;
 mod random;
+mod union_from_sorted_iterators;
 mod variable_word;
 
 pub use constants::*;
 pub use hasher_type::HasherType;
-pub(crate) use intersection_from_sorted_iterators::intersection_from_sorted_iterators;
+pub(crate) use intersection_from_sorted_iterators::{
+    contains_all_from_sorted_iterators, intersection_from_sorted_iterators,
+};
 pub use matrix::Matrix;
+#[cfg(feature = "alloc")]
+pub use matrix::RowMajorMatrix;
 pub(crate) use number::{FloatOps, Number, PositiveInteger};
 pub use random::*;
+pub(crate) use union_from_sorted_iterators::UnionFromSortedIterators;
 pub use variable_word::VariableWord;
 
 #[inline]
@@ -1,7 +1,12 @@
-//! Trait for Hashers that can be deaulted and shared among threads.
-use core::hash::Hasher;
+//! Trait for hasher builders that can be shared among threads.
+use core::hash::BuildHasher;
 
-/// Trait for Hashers that can be deaulted and shared among threads.
-pub trait HasherType: Default + Hasher + Send + Sync + Clone {}
+/// Trait for hasher builders that can be shared among threads.
+///
+/// # Implementative details
+/// The hasher builder is stored within the counter, so that seeded or keyed
+/// hashers may be used. When the hasher builder is zero-sized, as is the case
+/// for [`core::hash::BuildHasherDefault`], it does not take any space.
+pub trait HasherType: BuildHasher + Send + Sync + Clone {}
 
-impl<T> HasherType for T where T: Default + Hasher + Send + Sync + Clone {}
+impl<T> HasherType for T where T: BuildHasher + Send + Sync + Clone {}
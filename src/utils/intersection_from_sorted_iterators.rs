@@ -46,6 +46,32 @@ pub(crate) fn intersection_from_sorted_iterators<
     intersection
 }
 
+#[inline]
+/// Returns whether all of the values of the right decreasingly sorted iterator appear in
+/// the left decreasingly sorted iterator.
+pub(crate) fn contains_all_from_sorted_iterators<
+    T: Ord,
+    I: Iterator<Item = T>,
+    J: Iterator<Item = T>,
+>(
+    mut left: I,
+    right: J,
+) -> bool {
+    let mut maybe_left_value = left.next();
+    for right_value in right {
+        while maybe_left_value
+            .as_ref()
+            .is_some_and(|left_value| *left_value > right_value)
+        {
+            maybe_left_value = left.next();
+        }
+        if maybe_left_value.as_ref() != Some(&right_value) {
+            return false;
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
@@ -75,4 +101,34 @@ mod tests {
             assert_eq!(intersection_cardinality, unique_values_set);
         }
     }
+
+    #[test]
+    fn test_contains_all_from_sorted_iterators() {
+        let mut random_state = splitmix64(9_876_543_212_345);
+
+        for _ in 0..10 {
+            random_state = splitmix64(random_state);
+            let mut left = iter_var_len_random_values::<u64>(0, 1000, None, Some(random_state))
+                .collect::<Vec<_>>();
+            left.sort_unstable_by(|a, b| b.cmp(a));
+            let right = left.iter().copied().step_by(3).collect::<Vec<_>>();
+
+            assert!(contains_all_from_sorted_iterators(
+                left.iter().copied(),
+                right.iter().copied()
+            ));
+            assert!(contains_all_from_sorted_iterators(
+                left.iter().copied(),
+                core::iter::empty()
+            ));
+
+            let mut extended = right.clone();
+            extended.push(u64::MAX);
+            extended.sort_unstable_by(|a, b| b.cmp(a));
+            assert_eq!(
+                contains_all_from_sorted_iterators(left.iter().copied(), extended.iter().copied()),
+                left.contains(&u64::MAX)
+            );
+        }
+    }
 }
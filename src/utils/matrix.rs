@@ -27,3 +27,125 @@ where
         result
     }
 }
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A matrix whose dimensions are known at runtime, storing its values row after row.
+pub struct RowMajorMatrix<T> {
+    /// The values of the matrix, stored row after row.
+    values: Vec<T>,
+    /// The number of rows of the matrix.
+    number_of_rows: usize,
+    /// The number of columns of the matrix.
+    number_of_columns: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> RowMajorMatrix<T> {
+    #[inline]
+    #[must_use]
+    /// Returns a new matrix with the provided dimensions, filled with the provided value.
+    ///
+    /// # Arguments
+    /// * `number_of_rows` - The number of rows of the matrix.
+    /// * `number_of_columns` - The number of columns of the matrix.
+    /// * `value` - The value of each cell of the matrix.
+    pub fn new(number_of_rows: usize, number_of_columns: usize, value: T) -> Self {
+        Self {
+            values: vec![value; number_of_rows * number_of_columns],
+            number_of_rows,
+            number_of_columns,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> RowMajorMatrix<T> {
+    #[inline]
+    #[must_use]
+    /// Returns the number of rows of the matrix.
+    pub fn number_of_rows(&self) -> usize {
+        self.number_of_rows
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of columns of the matrix.
+    pub fn number_of_columns(&self) -> usize {
+        self.number_of_columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the value in the provided cell, if it is within the matrix.
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        if row < self.number_of_rows && column < self.number_of_columns {
+            self.values.get(row * self.number_of_columns + column)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the provided row of the matrix.
+    ///
+    /// # Panics
+    /// If the row is not within the matrix.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.number_of_rows, "The row {row} is out of bounds.");
+        &self.values[row * self.number_of_columns..(row + 1) * self.number_of_columns]
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns an iterator over the rows of the matrix.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + '_ {
+        (0..self.number_of_rows).map(move |row| self.row(row))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the values of the matrix, stored row after row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    #[inline]
+    /// Returns the mutable values of the matrix, stored row after row.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the values of the matrix, stored row after row.
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> core::ops::Index<(usize, usize)> for RowMajorMatrix<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (row, column): (usize, usize)) -> &Self::Output {
+        self.get(row, column)
+            .expect("The cell should be within the matrix.")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> core::ops::IndexMut<(usize, usize)> for RowMajorMatrix<T> {
+    #[inline]
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.number_of_rows && column < self.number_of_columns,
+            "The cell should be within the matrix."
+        );
+        &mut self.values[row * self.number_of_columns + column]
+    }
+}
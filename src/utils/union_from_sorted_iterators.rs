@@ -0,0 +1,99 @@
+//! This module provides an iterator over the union of two sorted iterators.
+use core::cmp::Ordering;
+use core::iter::Peekable;
+
+#[derive(Debug, Clone)]
+/// Iterator over the unique values of two decreasingly sorted iterators.
+///
+/// # Implementative details
+/// Values that appear in both iterators are returned only once, and so are
+/// values that are repeated contiguously within the same iterator.
+pub(crate) struct UnionFromSortedIterators<
+    T: Ord + Copy,
+    I: Iterator<Item = T>,
+    J: Iterator<Item = T>,
+> {
+    /// The left iterator.
+    left: Peekable<I>,
+    /// The right iterator.
+    right: Peekable<J>,
+    /// The last value returned by the iterator.
+    last: Option<T>,
+}
+
+impl<T: Ord + Copy, I: Iterator<Item = T>, J: Iterator<Item = T>>
+    UnionFromSortedIterators<T, I, J>
+{
+    #[inline]
+    /// Creates a new union iterator from two decreasingly sorted iterators.
+    pub(crate) fn new(left: I, right: J) -> Self {
+        Self {
+            left: left.peekable(),
+            right: right.peekable(),
+            last: None,
+        }
+    }
+}
+
+impl<T: Ord + Copy, I: Iterator<Item = T>, J: Iterator<Item = T>> Iterator
+    for UnionFromSortedIterators<T, I, J>
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let value = match (self.left.peek(), self.right.peek()) {
+                (Some(left_value), Some(right_value)) => match left_value.cmp(right_value) {
+                    Ordering::Greater => self.left.next(),
+                    Ordering::Less => self.right.next(),
+                    Ordering::Equal => {
+                        self.right.next();
+                        self.left.next()
+                    }
+                },
+                (Some(_), None) => self.left.next(),
+                (None, Some(_)) => self.right.next(),
+                (None, None) => return None,
+            };
+
+            if value != self.last {
+                self.last = value;
+                return value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::prelude::{iter_var_len_random_values, splitmix64};
+
+    #[test]
+    fn test_union_from_sorted_iterators() {
+        let number_of_iterations = 10;
+        let mut random_state = splitmix64(9_876_543_456_789);
+
+        for _ in 0..number_of_iterations {
+            random_state = splitmix64(random_state);
+            let mut left = iter_var_len_random_values::<u8>(0, 1000, None, Some(random_state))
+                .collect::<Vec<_>>();
+            left.sort_unstable_by(|a, b| b.cmp(a));
+            random_state = splitmix64(random_state);
+            let mut right = iter_var_len_random_values::<u8>(0, 1000, None, Some(random_state))
+                .collect::<Vec<_>>();
+            right.sort_unstable_by(|a, b| b.cmp(a));
+
+            let union = UnionFromSortedIterators::new(left.iter().copied(), right.iter().copied())
+                .collect::<Vec<_>>();
+
+            let mut expected = left.iter().chain(right.iter()).copied().collect::<Vec<_>>();
+            expected.sort_unstable_by(|a, b| b.cmp(a));
+            expected.dedup();
+
+            assert_eq!(union, expected);
+        }
+    }
+}
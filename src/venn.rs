@@ -0,0 +1,386 @@
+//! Submodule providing the estimation of the cardinalities of the regions of the Venn diagram
+//! of several [`HyperLogLog`] counters.
+use crate::hyperloglog::improved_raw_estimate;
+use crate::prelude::*;
+
+/// The maximal number of counters whose Venn diagram may be estimated.
+pub const MAXIMAL_VENN_SETS: usize = 8;
+
+/// Number of rounds of iterative proportional fitting applied to the regions.
+const CONSISTENCY_ROUNDS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// The estimated cardinalities of the regions of the Venn diagram of several sets.
+///
+/// # Implementative details
+/// Each region is identified by the bitmask of the sets its elements belong to,
+/// where the i-th bit is set when the elements belong to the i-th set: for instance,
+/// the region `0b101` holds the elements belonging to the first and third sets,
+/// but not to the second one.
+pub struct VennRegions {
+    /// The number of sets in the diagram.
+    number_of_sets: usize,
+    /// The cardinalities of the regions, indexed by their bitmask.
+    cardinalities: [f64; 1 << MAXIMAL_VENN_SETS],
+}
+
+impl VennRegions {
+    #[inline]
+    #[must_use]
+    /// Returns the number of sets in the diagram.
+    pub fn number_of_sets(&self) -> usize {
+        self.number_of_sets
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of regions in the diagram, excluding the empty one.
+    pub fn number_of_regions(&self) -> usize {
+        (1 << self.number_of_sets) - 1
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the region identified by the provided bitmask.
+    ///
+    /// # Arguments
+    /// * `region` - The bitmask of the sets the elements of the region belong to.
+    ///
+    /// # Panics
+    /// If the bitmask refers to sets that are not in the diagram.
+    pub fn region(&self, region: u8) -> f64 {
+        assert!(
+            usize::from(region) <= self.number_of_regions(),
+            "The region ({region}) refers to sets beyond the {} in the diagram.",
+            self.number_of_sets
+        );
+        self.cardinalities[usize::from(region)]
+    }
+
+    #[inline]
+    /// Returns an iterator over the bitmasks and estimated cardinalities of the non-empty regions.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, f64)> + '_ {
+        self.cardinalities[1..=self.number_of_regions()]
+            .iter()
+            .zip(1_u8..=u8::MAX)
+            .map(|(&cardinality, region)| (region, cardinality))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the provided set.
+    ///
+    /// # Arguments
+    /// * `set` - The position of the set in the diagram.
+    ///
+    /// # Panics
+    /// If the set is not in the diagram.
+    pub fn cardinality(&self, set: usize) -> f64 {
+        assert!(
+            set < self.number_of_sets,
+            "The set ({set}) is not among the {} in the diagram.",
+            self.number_of_sets
+        );
+        self.iter()
+            .filter(|(region, _)| region & (1 << set) != 0)
+            .map(|(_, cardinality)| cardinality)
+            .sum()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the union of all of the sets.
+    pub fn union(&self) -> f64 {
+        self.iter().map(|(_, cardinality)| cardinality).sum()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the intersection of all of the sets.
+    pub fn intersection(&self) -> f64 {
+        self.cardinalities[self.number_of_regions()]
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType> HyperLogLog<P, B, R, H> {
+    #[inline]
+    /// Returns the estimated cardinalities of the regions of the Venn diagram of the provided counters.
+    ///
+    /// # Arguments
+    /// * `counters` - The counters, at most [`MAXIMAL_VENN_SETS`].
+    ///
+    /// # Errors
+    /// * If no counters or more than [`MAXIMAL_VENN_SETS`] counters are provided.
+    /// * If a counter in hash list mode cannot be converted to registers.
+    ///
+    /// # Implementative details
+    /// The cardinality of the union of each subset of the counters is estimated from the
+    /// histogram of the maxima of their registers, all computed in a single pass over the
+    /// registers. The regions are then obtained from the unions by inclusion-exclusion:
+    /// the elements belonging to the sets in a region and possibly others are those in the
+    /// union of all sets but not in the union of the sets outside of the region. As the
+    /// errors of the unions may yield negative regions, these are clamped to zero and the
+    /// regions are then rescaled by iterative proportional fitting, so that the regions of
+    /// each set sum to its estimated cardinality and all regions sum to the estimated union.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut first: HyperLogLog<Precision12, Bits6, <Precision12 as PackedRegister<Bits6>>::Array> =
+    ///     Default::default();
+    /// let mut second = first.clone();
+    /// let mut third = first.clone();
+    ///
+    /// first.extend(0..20_000_u64);
+    /// second.extend(10_000..30_000_u64);
+    /// third.extend(15_000..40_000_u64);
+    ///
+    /// let regions = HyperLogLog::estimate_venn_regions(&[first, second, third]).unwrap();
+    ///
+    /// assert_eq!(regions.number_of_regions(), 7);
+    /// // The elements from 15_000 to 20_000 belong to all of the sets.
+    /// assert!((regions.region(0b111) - 5_000.0).abs() < 1_500.0, "{}", regions.region(0b111));
+    /// // The elements from 30_000 to 40_000 belong only to the third set.
+    /// assert!((regions.region(0b100) - 10_000.0).abs() < 1_500.0, "{}", regions.region(0b100));
+    /// assert!((regions.union() - 40_000.0).abs() < 2_000.0, "{}", regions.union());
+    /// ```
+    pub fn estimate_venn_regions(counters: &[Self]) -> Result<VennRegions, Error> {
+        if counters.is_empty() {
+            return Err(Error::InvalidArgument(
+                "At least one counter is required to estimate the Venn regions.",
+            ));
+        }
+        if counters.len() > MAXIMAL_VENN_SETS {
+            return Err(Error::InvalidArgument(
+                "Too many counters were provided to estimate the Venn regions.",
+            ));
+        }
+
+        let number_of_sets = counters.len();
+        let number_of_regions = 1_usize << number_of_sets;
+
+        let histograms = Self::subset_histograms(counters)?;
+
+        let mut unions = [0.0_f64; 1 << MAXIMAL_VENN_SETS];
+        for (union, histogram) in unions[1..number_of_regions]
+            .iter_mut()
+            .zip(histograms[1..number_of_regions].iter())
+        {
+            *union = improved_raw_estimate::<P, B>(histogram);
+        }
+
+        // The elements belonging at most to the sets in a subset are those in the
+        // union of all sets but not in the union of the sets outside of the subset.
+        let all_sets = number_of_regions - 1;
+        let mut at_most = [0.0_f64; 1 << MAXIMAL_VENN_SETS];
+        for subset in 1..number_of_regions {
+            at_most[subset] = unions[all_sets] - unions[all_sets & !subset];
+        }
+
+        // By inclusion-exclusion over the subsets of each region, we obtain the
+        // elements belonging exactly to the sets in the region.
+        let mut cardinalities = [0.0_f64; 1 << MAXIMAL_VENN_SETS];
+        for (region, cardinality) in cardinalities
+            .iter_mut()
+            .enumerate()
+            .take(number_of_regions)
+            .skip(1)
+        {
+            let mut subset = region;
+            while subset > 0 {
+                if (region ^ subset).count_ones() % 2 == 0 {
+                    *cardinality += at_most[subset];
+                } else {
+                    *cardinality -= at_most[subset];
+                }
+                subset = (subset - 1) & region;
+            }
+            *cardinality = cardinality.max(0.0);
+        }
+
+        for _ in 0..CONSISTENCY_ROUNDS {
+            for set in 0..number_of_sets {
+                let target = unions[1 << set];
+                let current: f64 = (1..number_of_regions)
+                    .filter(|region| region & (1 << set) != 0)
+                    .map(|region| cardinalities[region])
+                    .sum();
+                if current > 0.0 {
+                    for region in (1..number_of_regions).filter(|region| region & (1 << set) != 0) {
+                        cardinalities[region] *= target / current;
+                    }
+                } else {
+                    // When all of the regions of the set were clamped, we attribute
+                    // its elements to the region exclusive to the set.
+                    cardinalities[1 << set] = target;
+                }
+            }
+            let current: f64 = cardinalities[1..number_of_regions].iter().sum();
+            if current > 0.0 {
+                for cardinality in &mut cardinalities[1..number_of_regions] {
+                    *cardinality *= unions[all_sets] / current;
+                }
+            }
+        }
+
+        Ok(VennRegions {
+            number_of_sets,
+            cardinalities,
+        })
+    }
+
+    #[inline]
+    /// Returns the histogram of the maxima of the registers of each subset of the provided counters.
+    ///
+    /// # Errors
+    /// If a counter in hash list mode cannot be converted to registers.
+    fn subset_histograms(counters: &[Self]) -> Result<Vec<[u32; 64]>, Error> {
+        let number_of_regions = 1_usize << counters.len();
+
+        // The counters in hash list mode are converted to registers, so that
+        // all of the registers may be visited in lockstep.
+        let converted = counters
+            .iter()
+            .filter(|counter| counter.is_hash_list())
+            .map(|counter| {
+                let mut counter = counter.clone();
+                counter.convert_hash_list_to_hyperloglog()?;
+                Ok(counter)
+            })
+            .collect::<Result<Vec<Self>, Error>>()?;
+        let mut converted = converted.iter();
+        let mut registers: Vec<R::Iter<'_>> = counters
+            .iter()
+            .filter_map(|counter| {
+                if counter.is_hash_list() {
+                    converted.next()
+                } else {
+                    Some(counter)
+                }
+            })
+            .map(|counter| counter.registers.iter_registers())
+            .collect();
+
+        let mut histograms = vec![[0_u32; 64]; number_of_regions];
+        let mut values = [0_u8; MAXIMAL_VENN_SETS];
+        let mut maxima = [0_u8; 1 << MAXIMAL_VENN_SETS];
+        for _ in 0..1_usize << P::EXPONENT {
+            for (value, register) in values
+                .iter_mut()
+                .zip(registers.iter_mut().filter_map(Iterator::next))
+            {
+                *value = register;
+            }
+            // The maximum of each subset extends the maximum of the subset
+            // without its lowest set with the register of that set.
+            for subset in 1..number_of_regions {
+                let lowest = subset.trailing_zeros() as usize;
+                maxima[subset] = maxima[subset & (subset - 1)].max(values[lowest]);
+                histograms[subset][usize::from(maxima[subset])] += 1;
+            }
+        }
+
+        Ok(histograms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperloglog_derive::test_precisions_and_bits;
+
+    #[test_precisions_and_bits]
+    fn test_estimate_venn_regions<P, B: Bits>()
+    where
+        P: PackedRegister<B>,
+    {
+        let mut random_state = splitmix64(P::EXPONENT.into());
+        let mut errors = 0.0;
+        let mut samples = 0.0;
+
+        for number_of_sets in 1..=4_usize {
+            random_state = splitmix64(random_state);
+            let mut counters: Vec<HyperLogLog<P, B, <P as PackedRegister<B>>::Vec>> =
+                vec![HyperLogLog::default(); number_of_sets];
+            let mut arrays: Vec<HyperLogLog<P, B, <P as PackedRegister<B>>::Array>> =
+                vec![HyperLogLog::default(); number_of_sets];
+            let mut exact = [0.0_f64; 1 << MAXIMAL_VENN_SETS];
+
+            // Each region receives a different number of elements.
+            for (region, exact) in exact
+                .iter_mut()
+                .enumerate()
+                .take(1 << number_of_sets)
+                .skip(1)
+            {
+                let size = [0_u32, 1_000, 2_000, 3_000, 4_000][region % 5];
+                for element in 0..size {
+                    let hash =
+                        splitmix64(random_state ^ ((region as u64) << 32) ^ u64::from(element));
+                    for (set, (counter, array)) in
+                        counters.iter_mut().zip(arrays.iter_mut()).enumerate()
+                    {
+                        if region & (1 << set) != 0 {
+                            counter.insert(&hash);
+                            array.insert(&hash);
+                        }
+                    }
+                }
+                *exact = f64::from(size);
+            }
+
+            let regions = HyperLogLog::estimate_venn_regions(&counters).unwrap();
+            assert_eq!(regions.number_of_sets(), number_of_sets);
+            assert_eq!(regions.iter().count(), regions.number_of_regions());
+            // The regions do not depend on the storage of the registers.
+            assert_eq!(
+                HyperLogLog::estimate_venn_regions(&arrays).unwrap(),
+                regions
+            );
+
+            let union: f64 = exact.iter().sum();
+            for (region, cardinality) in regions.iter() {
+                assert!(cardinality >= 0.0);
+                errors += (cardinality - exact[usize::from(region)]).abs() / union;
+                samples += 1.0;
+            }
+
+            // The regions sum to the estimated union.
+            let mut merged = counters[0].clone();
+            for counter in &counters[1..] {
+                merged.merge(counter);
+            }
+            if merged.is_hash_list() {
+                merged.convert_hash_list_to_hyperloglog().unwrap();
+            }
+            assert!(
+                (regions.union() - merged.improved_estimate_cardinality()).abs()
+                    <= 1e-6 * regions.union()
+            );
+        }
+
+        assert!(
+            errors / samples <= P::error_rate(),
+            "The error rate of the regions ({}) is higher than expected ({}).",
+            errors / samples,
+            P::error_rate()
+        );
+    }
+
+    #[test]
+    fn test_estimate_venn_regions_errors() {
+        let counters: Vec<
+            HyperLogLog<Precision6, Bits6, <Precision6 as PackedRegister<Bits6>>::Array>,
+        > = vec![HyperLogLog::default(); MAXIMAL_VENN_SETS + 1];
+        assert!(HyperLogLog::estimate_venn_regions(&counters[..0]).is_err());
+        assert!(HyperLogLog::estimate_venn_regions(&counters).is_err());
+
+        let regions = HyperLogLog::estimate_venn_regions(&counters[..MAXIMAL_VENN_SETS]).unwrap();
+        assert_eq!(regions.number_of_regions(), 255);
+        assert!(regions.union().abs() < f64::EPSILON);
+        assert!(regions.intersection().abs() < f64::EPSILON);
+    }
+}
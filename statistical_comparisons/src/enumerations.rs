@@ -1,10 +1,11 @@
 //! This module contains the enumerations used in the benchmarks.
 
 use crate::proxy_implementations::{
-    AlecHLL, CloudFlareHLL, HasherBuilderAssociated, HyperThreeBits, HyperTwoBits, RustHLL,
-    SimpleHLL, TabacHLL, TabacHLLPlusPlus,
+    AlecHLL, CloudFlareHLL, HasherBuilderAssociated, HyperThreeBits, HyperTwoBits, ImprovedHLL,
+    RustHLL, SimpleHLL, TabacHLL, TabacHLLPlusPlus,
 };
 use crate::traits::TransparentMemSize;
+use core::hash::BuildHasherDefault;
 use hyperloglog_rs::prelude::*;
 use hypertwobits::h2b::{
     M1024 as M1024H2B, M128 as M128H2B, M2048 as M2048H2B, M256 as M256H2B, M4096 as M4096H2B,
@@ -68,5 +69,6 @@ pub enum HLLVariants<
     RustHyperLogLog(RustHLL<P>),
     CE(CloudFlareHLL<EXPONENT, BITS, H>),
     SimpleHLL(SimpleHLL<H, EXPONENT>),
-    HLL(HyperLogLog<P, B, <P as PackedRegister<B>>::Array, H>),
+    HLL(HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<H>>),
+    ImprovedHLL(ImprovedHLL<P, B, H>),
 }
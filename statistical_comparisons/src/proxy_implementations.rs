@@ -1,5 +1,5 @@
 //! This module contains implementations of the `Set` trait for various HyperLogLog
-use core::hash::{BuildHasher, Hash, Hasher};
+use core::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
 
 use hyperloglog_rs::prelude::Bits;
 use hyperloglog_rs::prelude::HyperLogLog;
@@ -7,7 +7,7 @@ use hyperloglog_rs::prelude::PackedRegister;
 use mem_dbg::{MemDbg, MemSize};
 
 use cardinality_estimator::CardinalityEstimator;
-use hyperloglog_rs::prelude::{HasherType, Precision};
+use hyperloglog_rs::prelude::Precision;
 use hyperloglogplus::HyperLogLog as TabacHyperLogLog;
 use hyperloglogplus::HyperLogLogPF as TabacHyperLogLogPF;
 use hyperloglogplus::HyperLogLogPlus as TabacHyperLogLogPlus;
@@ -24,7 +24,7 @@ use test_utils::prelude::Set;
 use wyhash::WyHash;
 
 /// Trait to associate a Hasher with a HasherBuilder
-pub trait HasherBuilderAssociated: HasherType + MemSize {
+pub trait HasherBuilderAssociated: Hasher + Default + Clone + Send + Sync + MemSize {
     /// The associated HasherBuilder
     type Builder: BuildHasher + Default + Clone + Send + Sync + MemSize;
 }
@@ -48,7 +48,7 @@ impl HasherBuilderAssociated for ahash::AHasher {
 #[derive(Clone, MemSize)]
 /// Wrapper for the Uncorrected implementation
 pub struct HashListCorrectedImprinted<P: Precision + PackedRegister<B>, B: Bits> {
-    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, WyHash>,
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<WyHash>>,
 }
 
 impl<P: Precision + PackedRegister<B>, B: Bits> Default for HashListCorrectedImprinted<P, B> {
@@ -89,10 +89,65 @@ impl<P: Precision + PackedRegister<B>, B: Bits> Set for HashListCorrectedImprint
     }
 }
 
+#[derive(Clone, MemSize)]
+/// Wrapper estimating the cardinality with the improved raw estimator instead of the correction tables
+pub struct ImprovedHLL<P: Precision + PackedRegister<B>, B: Bits, H: HasherBuilderAssociated> {
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<H>>,
+}
+
+impl<P: Precision + PackedRegister<B>, B: Bits, H: HasherBuilderAssociated> Default
+    for ImprovedHLL<P, B, H>
+{
+    fn default() -> Self {
+        Self {
+            hll: HyperLogLog::default(),
+        }
+    }
+}
+
+impl<P: Precision + PackedRegister<B>, B: Bits, H: HasherBuilderAssociated> Set
+    for ImprovedHLL<P, B, H>
+{
+    #[inline]
+    fn cardinality(&self) -> f64 {
+        self.hll.improved_estimate_cardinality()
+    }
+
+    #[inline]
+    fn insert_element(&mut self, value: u64) {
+        self.hll.insert(&value);
+    }
+
+    #[inline]
+    fn model_name(&self) -> String {
+        format!(
+            "ImprovedHLL<P{}, B{}> + {}",
+            P::EXPONENT,
+            B::NUMBER_OF_BITS,
+            core::any::type_name::<H>().split("::").last().unwrap()
+        )
+    }
+
+    #[inline]
+    fn union(&self, other: &Self) -> f64 {
+        self.hll.improved_estimate_union_cardinality(&other.hll)
+    }
+
+    #[inline]
+    fn precision(&self) -> Option<u8> {
+        Some(P::EXPONENT)
+    }
+
+    #[inline]
+    fn bits(&self) -> Option<u8> {
+        Some(B::NUMBER_OF_BITS)
+    }
+}
+
 #[derive(Clone, MemSize)]
 /// Wrapper for the UncorrectedNoHashList implementation
 pub struct UncorrectedNoHashList<P: Precision + PackedRegister<B>, B: Bits> {
-    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, WyHash>,
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<WyHash>>,
 }
 
 impl<P: Precision + PackedRegister<B>, B: Bits> Default for UncorrectedNoHashList<P, B> {
@@ -131,13 +186,13 @@ impl<P: Precision + PackedRegister<B>, B: Bits> Set for UncorrectedNoHashList<P,
 
 #[derive(Debug, Clone, Default, MemDbg, MemSize)]
 /// Wrapper for the SimpleHyperLogLog implementation
-pub struct SimpleHLL<H: HasherType, const P: usize> {
+pub struct SimpleHLL<H: HasherBuilderAssociated, const P: usize> {
     estimator: SimpleHyperLogLog<H, P>,
 }
 
 #[derive(Debug, Clone, Default, MemDbg, MemSize)]
 /// Wrapper for the CloudFlare HyperLogLog implementation
-pub struct CloudFlareHLL<const P: usize, const B: usize, H: HasherType> {
+pub struct CloudFlareHLL<const P: usize, const B: usize, H: HasherBuilderAssociated> {
     estimator: CardinalityEstimator<u64, H, P, B>,
 }
 
@@ -244,7 +299,7 @@ impl<P: Precision> Default for AlecHLL<P> {
     }
 }
 
-impl<H: HasherType, const P: usize> Set for SimpleHLL<H, P> {
+impl<H: HasherBuilderAssociated, const P: usize> Set for SimpleHLL<H, P> {
     #[inline]
     fn insert_element(&mut self, item: u64) {
         self.estimator.add_object(&item);
@@ -374,7 +429,7 @@ impl<P: Precision> Set for SourMash<P> {
     }
 }
 
-impl<H: HasherType, const P: usize, const B: usize> Set for CloudFlareHLL<P, B, H> {
+impl<H: HasherBuilderAssociated, const P: usize, const B: usize> Set for CloudFlareHLL<P, B, H> {
     #[inline]
     fn insert_element(&mut self, item: u64) {
         self.estimator.insert(&item);
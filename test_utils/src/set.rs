@@ -3,6 +3,7 @@
 use hyperloglog_rs::prelude::*;
 use mem_dbg::MemSize;
 use std::collections::HashSet;
+use std::hash::BuildHasherDefault;
 use wyhash::WyHash;
 
 /// A trait to represent a set.
@@ -71,7 +72,11 @@ impl<H: HasherType, P: Precision, B: Bits, R: Registers<P, B>> Set for HyperLogL
             "HLL<P{}, B{}> + {}",
             P::EXPONENT,
             B::NUMBER_OF_BITS,
-            core::any::type_name::<H>().split("::").last().unwrap()
+            core::any::type_name::<H>()
+                .split("::")
+                .last()
+                .unwrap()
+                .trim_end_matches('>')
         )
     }
 
@@ -89,7 +94,7 @@ impl<H: HasherType, P: Precision, B: Bits, R: Registers<P, B>> Set for HyperLogL
 #[derive(Clone, MemSize)]
 /// Wrapper for the Uncorrected implementation
 pub struct UncorrectedFullyImprinted<P: Precision + PackedRegister<B>, B: Bits> {
-    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, WyHash>,
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<WyHash>>,
 }
 
 impl<P: Precision + PackedRegister<B>, B: Bits> Default for UncorrectedFullyImprinted<P, B> {
@@ -140,7 +145,7 @@ impl<P: Precision + PackedRegister<B>, B: Bits> Set for UncorrectedFullyImprinte
 #[derive(Clone, MemSize, Default)]
 /// Wrapper for the Uncorrected implementation
 pub struct Uncorrected<P: Precision + PackedRegister<B>, B: Bits> {
-    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, WyHash>,
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<WyHash>>,
 }
 
 impl<P: Precision + PackedRegister<B>, B: Bits> Uncorrected<P, B> {
@@ -191,7 +196,7 @@ impl<P: Precision + PackedRegister<B>, B: Bits> Set for Uncorrected<P, B> {
 /// Wrapper using exclusively linear counting
 /// for the estimation of the cardinality.
 pub struct LinearCountingHashList<P: Precision + PackedRegister<B>, B: Bits> {
-    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, WyHash>,
+    hll: HyperLogLog<P, B, <P as PackedRegister<B>>::Array, BuildHasherDefault<WyHash>>,
 }
 
 impl<P: Precision + PackedRegister<B>, B: Bits> Set for LinearCountingHashList<P, B> {
@@ -7,7 +7,7 @@ pub fn test_approximated_counter_at_precision_and_bits<
     P: Precision,
     B: Bits,
     R: Registers<P, B>,
-    H: HasherType,
+    H: HasherType + Default,
 >() {
     let number_of_elements = 200_000;
     let mut total_cardinality_error_rate = 0.0;
@@ -118,6 +118,6 @@ pub fn test_approximated_counter_at_precision_and_bits<
 // }
 
 #[test_estimator]
-fn test_hyperloglog<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+fn test_hyperloglog<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + Default>() {
     test_approximated_counter_at_precision_and_bits::<P, B, R, H>();
 }